@@ -1,5 +1,6 @@
 use crate::MessageResult;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
@@ -28,6 +29,20 @@ impl NewADSBJsonMessage for str {
     }
 }
 
+/// The decoder that wrote a JSON aircraft record.
+///
+/// readsb records decode directly into `ADSBJsonMessage`.
+/// dump1090-fa records have no registration, type or receiver distance, may lack `type` and `rc`, and use different names on older builds,
+/// so they are read leniently and the missing fields are filled with defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonDialect {
+    #[default]
+    #[serde(rename = "readsb")]
+    Readsb,
+    #[serde(rename = "dump1090-fa")]
+    Dump1090Fa,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct ADSBJsonMessage {
@@ -41,14 +56,41 @@ pub struct ADSBJsonMessage {
     pub aircraft_registration: String, // registration
     #[serde(skip_serializing_if = "Option::is_none", rename = "t")]
     pub aircraft_type: Option<String>,
-    pub alt_baro: Altitude, // altitude
+    #[serde(skip_serializing_if = "Option::is_none", rename = "desc")]
+    pub description: Option<String>, // aircraft type description, from the long database
+    #[serde(skip_serializing_if = "Option::is_none", rename = "ownOp")]
+    pub owner_operator: Option<String>, // owner or operator, from the long database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>, // year of manufacture, from the long database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_baro: Option<Altitude>, // altitude
     #[serde(skip_serializing_if = "Option::is_none")]
     pub alt_geom: Option<i32>, // altitude
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gs: Option<f32>, // ground speed
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub ias: Option<i32>, // indicated air speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tas: Option<i32>, // true air speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mach: Option<f32>, // mach number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wd: Option<i32>, // wind direction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ws: Option<i32>, // wind speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oat: Option<i32>, // outer/static air temperature, C
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tat: Option<i32>, // total air temperature, C
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub track: Option<f32>, // track
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_rate: Option<f32>, // rate of change of track, degrees/second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roll: Option<f32>, // roll, degrees, negative is left roll
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mag_heading: Option<f32>, // heading, degrees clockwise from magnetic north
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub baro_rate: Option<i32>, // vertical rate
     #[serde(skip_serializing_if = "Option::is_none")]
     pub geom_rate: Option<i32>, // vertical rate
@@ -63,20 +105,28 @@ pub struct ADSBJsonMessage {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nav_altitude_mcp: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_altitude_fms: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub nav_heading: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub true_heading: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nav_modes: Option<Vec<NavModes>>,
-    pub lat: f32, // latitude
-    pub lon: f32, // longitude
-    pub nic: i32, // Navigation Integrity Category
-    pub rc: i32,  // Radius of Containment, meter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f32>, // latitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f32>, // longitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic: Option<i32>, // Navigation Integrity Category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rc: Option<i32>, // Radius of Containment, meter
     #[serde(skip_serializing_if = "Option::is_none")]
     pub seen_pos: Option<f64>, // how long ago (in seconds before "now") the position was last updated
     pub seen: f64, // how long ago (in seconds before "now") the message was last received
-    pub r_dst: f32, // distance from receiver
-    pub r_dir: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r_dst: Option<f32>, // distance from receiver
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r_dir: Option<f32>, // direction from receiver
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<i32>, // version
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -98,12 +148,38 @@ pub struct ADSBJsonMessage {
     pub spi: Option<i8>, // Flight status special position identification bit (2.2.3.2.3.2)
     pub mlat: Vec<String>, // MLAT
     pub tisb: Vec<String>, // TIS-B
-    pub messages: i32,     // number of messages
+    pub messages: i32, // number of messages
     pub rssi: f32,
     #[serde(skip_serializing_if = "Option::is_none", rename = "dbFlags")]
     pub dbflags: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub calc_track: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "lastPosition")]
+    pub last_position: Option<LastPosition>, // last known position, once the position is older than 60 seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rr_lat: Option<f32>, // rough latitude, from the receivers that heard the aircraft
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rr_lon: Option<f32>, // rough longitude, from the receivers that heard the aircraft
+    #[serde(skip_serializing_if = "Option::is_none", rename = "gpsOkBefore")]
+    pub gps_ok_before: Option<f64>, // Unix timestamp of the last good GPS position before suspected jamming
+    #[serde(skip_serializing_if = "Option::is_none", rename = "gpsOkLat")]
+    pub gps_ok_lat: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "gpsOkLon")]
+    pub gps_ok_lon: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dst: Option<f32>, // distance from the point of an API query, nautical miles
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<f32>, // direction from the point of an API query, degrees
+}
+
+/// The last known position of an aircraft, kept by the aggregator APIs once the current position is too old.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LastPosition {
+    pub lat: f32,      // latitude
+    pub lon: f32,      // longitude
+    pub nic: i32,      // Navigation Integrity Category
+    pub rc: i32,       // Radius of Containment, meter
+    pub seen_pos: f64, // how long ago (in seconds before "now") the position was last updated
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -120,7 +196,7 @@ impl Default for Altitude {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NavModes {
     #[serde(rename = "althold")]
     AltHold,
@@ -130,25 +206,190 @@ pub enum NavModes {
     VNAV,
     #[serde(rename = "lnav")]
     LNAV,
+    #[serde(rename = "approach")]
+    Approach,
     #[serde(rename = "tcas")]
     TCAS,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum SilType {
     #[serde(rename = "perhour")]
     PerHour,
+    #[serde(rename = "persample")]
+    PerSample,
     #[serde(rename = "unknown")]
+    #[default]
     Unknown,
 }
 
-impl Default for SilType {
-    fn default() -> Self {
-        SilType::Unknown
+/// A dump1090-fa aircraft record, covering the fields written by current and older builds.
+#[derive(Deserialize)]
+struct Dump1090FaJsonMessage {
+    now: f64,
+    hex: String,
+    #[serde(rename = "type")]
+    adsb_type: Option<String>,
+    flight: Option<String>,
+    #[serde(alias = "altitude")]
+    alt_baro: Option<Altitude>,
+    alt_geom: Option<i32>,
+    #[serde(alias = "speed")]
+    gs: Option<f32>,
+    ias: Option<i32>,
+    tas: Option<i32>,
+    mach: Option<f32>,
+    track: Option<f32>,
+    track_rate: Option<f32>,
+    roll: Option<f32>,
+    mag_heading: Option<f32>,
+    true_heading: Option<f32>,
+    #[serde(alias = "vert_rate")]
+    baro_rate: Option<i32>,
+    geom_rate: Option<i32>,
+    squawk: Option<String>,
+    emergency: Option<String>,
+    category: Option<String>,
+    nav_qnh: Option<f32>,
+    nav_altitude_mcp: Option<i32>,
+    nav_altitude_fms: Option<i32>,
+    nav_heading: Option<f32>,
+    nav_modes: Option<Vec<NavModes>>,
+    lat: Option<f32>,
+    lon: Option<f32>,
+    nic: Option<i32>,
+    rc: Option<i32>,
+    seen_pos: Option<f64>,
+    version: Option<i32>,
+    nic_baro: Option<i8>,
+    nac_p: Option<i8>,
+    nac_v: Option<i8>,
+    sil: Option<i8>,
+    sil_type: Option<SilType>,
+    gva: Option<i8>,
+    sda: Option<i8>,
+    alert: Option<i8>,
+    spi: Option<i8>,
+    #[serde(default)]
+    mlat: Vec<String>,
+    #[serde(default)]
+    tisb: Vec<String>,
+    #[serde(default)]
+    messages: i32,
+    #[serde(default)]
+    seen: f64,
+    #[serde(default)]
+    rssi: f32,
+}
+
+impl From<Dump1090FaJsonMessage> for ADSBJsonMessage {
+    fn from(message: Dump1090FaJsonMessage) -> Self {
+        // Older builds leave out the address type, so derive it from the source of the position.
+        let adsb_type: String = match message.adsb_type {
+            Some(adsb_type) => adsb_type,
+            None if message.mlat.iter().any(|field| field == "lat") => "mlat".to_string(),
+            None if message.tisb.iter().any(|field| field == "lat") => "tisb_icao".to_string(),
+            None if message.version.is_some() => "adsb_icao".to_string(),
+            None => "unknown".to_string(),
+        };
+        ADSBJsonMessage {
+            now: message.now,
+            hex: message.hex,
+            adsb_type,
+            flight: message.flight,
+            aircraft_registration: String::new(),
+            aircraft_type: None,
+            alt_baro: message.alt_baro,
+            alt_geom: message.alt_geom,
+            gs: message.gs,
+            ias: message.ias,
+            tas: message.tas,
+            mach: message.mach,
+            wd: None,
+            ws: None,
+            oat: None,
+            tat: None,
+            track: message.track,
+            track_rate: message.track_rate,
+            roll: message.roll,
+            mag_heading: message.mag_heading,
+            baro_rate: message.baro_rate,
+            geom_rate: message.geom_rate,
+            squawk: message.squawk,
+            emergency: message.emergency,
+            category: message.category,
+            nav_qnh: message.nav_qnh,
+            nav_altitude_mcp: message.nav_altitude_mcp,
+            nav_altitude_fms: message.nav_altitude_fms,
+            nav_heading: message.nav_heading,
+            true_heading: message.true_heading,
+            nav_modes: message.nav_modes,
+            lat: message.lat,
+            lon: message.lon,
+            nic: message.nic,
+            rc: message.rc,
+            seen_pos: message.seen_pos,
+            seen: message.seen,
+            r_dst: None,
+            r_dir: None,
+            version: message.version,
+            nic_baro: message.nic_baro,
+            nac_p: message.nac_p,
+            nac_v: message.nac_v,
+            sil: message.sil,
+            sil_type: message.sil_type.unwrap_or_default(),
+            gva: message.gva,
+            sda: message.sda,
+            alert: message.alert,
+            spi: message.spi,
+            mlat: message.mlat,
+            tisb: message.tisb,
+            messages: message.messages,
+            rssi: message.rssi,
+            dbflags: None,
+            calc_track: None,
+            ..Default::default()
+        }
     }
 }
 
 impl ADSBJsonMessage {
+    /// Returns the latitude and longitude, if the message carries a position.
+    pub fn position(&self) -> Option<(f32, f32)> {
+        self.lat.zip(self.lon)
+    }
+
+    /// Returns whether the position came from multilateration rather than from the aircraft itself.
+    pub fn is_mlat_position(&self) -> bool {
+        self.adsb_type == "mlat" || self.mlat.iter().any(|field| field == "lat")
+    }
+
+    /// Decodes a single JSON aircraft record written by the decoder `dialect`.
+    pub fn from_dialect(json: &str, dialect: JsonDialect) -> MessageResult<ADSBJsonMessage> {
+        match dialect {
+            JsonDialect::Readsb => serde_json::from_str(json),
+            JsonDialect::Dump1090Fa => match serde_json::from_str::<Dump1090FaJsonMessage>(json) {
+                Err(parse_error) => Err(parse_error),
+                Ok(message) => Ok(message.into()),
+            },
+        }
+    }
+
+    /// Decodes a JSON aircraft record that has already been parsed to a `Value`.
+    pub(crate) fn from_value_dialect(
+        value: Value,
+        dialect: JsonDialect,
+    ) -> MessageResult<ADSBJsonMessage> {
+        match dialect {
+            JsonDialect::Readsb => serde_json::from_value(value),
+            JsonDialect::Dump1090Fa => match serde_json::from_value::<Dump1090FaJsonMessage>(value)
+            {
+                Err(parse_error) => Err(parse_error),
+                Ok(message) => Ok(message.into()),
+            },
+        }
+    }
+
     /// Converts `ADSBsMessage` to `String`.
     pub fn to_string(&self) -> MessageResult<String> {
         serde_json::to_string(self)
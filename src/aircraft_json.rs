@@ -0,0 +1,203 @@
+use crate::adsb_json::{ADSBJsonMessage, JsonDialect};
+use crate::MessageResult;
+use serde::de::Error;
+use serde::ser::{Error as SerError, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a complete `aircraft.json` document, as written by readsb or tar1090, and will not consume the source.
+pub trait NewAircraftSnapshot {
+    fn to_aircraft_snapshot(&self) -> MessageResult<AircraftSnapshot>;
+}
+
+/// Implementing `.to_aircraft_snapshot()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewAircraftSnapshot for String {
+    fn to_aircraft_snapshot(&self) -> MessageResult<AircraftSnapshot> {
+        serde_json::from_str(self)
+    }
+}
+
+/// Supporting `.to_aircraft_snapshot()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewAircraftSnapshot for str {
+    fn to_aircraft_snapshot(&self) -> MessageResult<AircraftSnapshot> {
+        serde_json::from_str(self)
+    }
+}
+
+/// A polled `aircraft.json` document.
+///
+/// The entries of the document do not carry their own `now`, so each `ADSBJsonMessage` in `aircraft` has it filled from the document.
+/// Entries without a position, such as Mode-S only aircraft, are kept with their position fields left empty.
+/// An entry that cannot be decoded is logged and left out rather than failing the whole document.
+///
+/// Serialising a snapshot writes the same envelope, with `now` left out of the entries, so it can be served to tar1090.
+#[derive(Debug, Clone, Default)]
+pub struct AircraftSnapshot {
+    pub now: f64,                       // Unix timestamp the document was written
+    pub messages: u64,                  // number of messages received by the decoder
+    pub aircraft: Vec<ADSBJsonMessage>, // aircraft in the document
+}
+
+#[derive(Deserialize)]
+struct RawAircraftSnapshot {
+    now: f64,
+    messages: u64,
+    aircraft: Vec<Map<String, Value>>,
+}
+
+impl<'de> Deserialize<'de> for AircraftSnapshot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw_snapshot: RawAircraftSnapshot = RawAircraftSnapshot::deserialize(deserializer)?;
+        match raw_snapshot.into_snapshot(JsonDialect::Readsb) {
+            Err(entry_error) => Err(D::Error::custom(entry_error)),
+            Ok(snapshot) => Ok(snapshot),
+        }
+    }
+}
+
+impl RawAircraftSnapshot {
+    fn into_snapshot(self, dialect: JsonDialect) -> MessageResult<AircraftSnapshot> {
+        let mut aircraft: Vec<ADSBJsonMessage> = Vec::with_capacity(self.aircraft.len());
+        for mut entry in self.aircraft {
+            fill_entry_defaults(&mut entry, self.now);
+            let hex: Option<Value> = entry.get("hex").cloned();
+            match ADSBJsonMessage::from_value_dialect(Value::Object(entry), dialect) {
+                Err(entry_error) => debug!("Skipping aircraft {:?}: {}", hex, entry_error),
+                Ok(message) => aircraft.push(message),
+            }
+        }
+        Ok(AircraftSnapshot {
+            now: self.now,
+            messages: self.messages,
+            aircraft,
+        })
+    }
+}
+
+impl Serialize for AircraftSnapshot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entries: Vec<SnapshotEntry> = self.aircraft.iter().map(SnapshotEntry).collect();
+        let mut snapshot = serializer.serialize_struct("AircraftSnapshot", 3)?;
+        snapshot.serialize_field("now", &self.now)?;
+        snapshot.serialize_field("messages", &self.messages)?;
+        snapshot.serialize_field("aircraft", &entries)?;
+        snapshot.end()
+    }
+}
+
+/// An `ADSBJsonMessage` written as an `aircraft.json` entry, which leaves out `now`.
+///
+/// The defaults filled in when decoding an entry are left out again, so readsb output decodes and encodes unchanged.
+pub(crate) struct SnapshotEntry<'a>(pub(crate) &'a ADSBJsonMessage);
+
+impl Serialize for SnapshotEntry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Going through the JSON text keeps the `f32` fields at the precision they are written with.
+        match serde_json::to_string(self.0).and_then(|json| serde_json::from_str(&json)) {
+            Err(value_error) => Err(S::Error::custom(value_error)),
+            Ok(Value::Object(mut entry)) => {
+                entry.remove("now");
+                if entry.get("r") == Some(&Value::from("")) {
+                    entry.remove("r");
+                }
+                if !entry.contains_key("sil")
+                    && entry.get("sil_type") == Some(&Value::from("unknown"))
+                {
+                    entry.remove("sil_type");
+                }
+                entry.serialize(serializer)
+            }
+            Ok(other) => other.serialize(serializer),
+        }
+    }
+}
+
+impl AircraftSnapshot {
+    /// Decodes an `aircraft.json` document written by the decoder `dialect`.
+    pub fn from_dialect(document: &str, dialect: JsonDialect) -> MessageResult<AircraftSnapshot> {
+        match serde_json::from_str::<RawAircraftSnapshot>(document) {
+            Err(parse_error) => Err(parse_error),
+            Ok(raw_snapshot) => raw_snapshot.into_snapshot(dialect),
+        }
+    }
+
+    /// Builds a snapshot taken at `now` from a collection of messages, such as the latest message held for each tracked aircraft.
+    ///
+    /// `seen` and `seen_pos` are recomputed relative to `now` from the time each message was received,
+    /// and `messages` is the total of the per aircraft message counts.
+    pub fn from_messages<'a, T>(now: f64, messages: T) -> Self
+    where
+        T: IntoIterator<Item = &'a ADSBJsonMessage>,
+    {
+        let aircraft: Vec<ADSBJsonMessage> = messages
+            .into_iter()
+            .map(|message| ADSBJsonMessage {
+                now,
+                seen: seen_since(now, message.now - message.seen, 1),
+                seen_pos: message
+                    .seen_pos
+                    .map(|seen_pos| seen_since(now, message.now - seen_pos, 3)),
+                ..message.clone()
+            })
+            .collect();
+        AircraftSnapshot {
+            now,
+            messages: aircraft
+                .iter()
+                .map(|message| message.messages.max(0) as u64)
+                .sum(),
+            aircraft,
+        }
+    }
+
+    /// Converts `AircraftSnapshot` to an `aircraft.json` `String`.
+    pub fn to_string(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `AircraftSnapshot` to an `aircraft.json` `String` and appends a `\n` to the end.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        match serde_json::to_string(self) {
+            Err(to_string_error) => Err(to_string_error),
+            Ok(string) => Ok(format!("{}\n", string)),
+        }
+    }
+
+    /// Converts `AircraftSnapshot` to an `aircraft.json` `String` encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
+        match self.to_string() {
+            Err(conversion_failed) => Err(conversion_failed),
+            Ok(string) => Ok(string.into_bytes()),
+        }
+    }
+}
+
+/// Fills the fields readsb leaves out of an `aircraft.json` entry, such as `r` for aircraft missing from the database
+/// and `sil_type` before the quality fields are known, and the `now` of the document the entry came from.
+pub(crate) fn fill_entry_defaults(entry: &mut Map<String, Value>, now: f64) {
+    let defaults: [(&str, Value); 6] = [
+        ("now", Value::from(now)),
+        ("r", Value::from("")),
+        ("sil_type", Value::from("unknown")),
+        ("mlat", Value::Array(Vec::new())),
+        ("tisb", Value::Array(Vec::new())),
+        ("rssi", Value::from(0)),
+    ];
+    for (key, value) in defaults {
+        entry.entry(key).or_insert(value);
+    }
+}
+
+/// Returns the seconds between `received` and `now`, rounded to `decimals` places as readsb writes them.
+fn seen_since(now: f64, received: f64, decimals: i32) -> f64 {
+    let scale: f64 = 10f64.powi(decimals);
+    ((now - received).max(0.0) * scale).round() / scale
+}
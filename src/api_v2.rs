@@ -0,0 +1,126 @@
+use crate::adsb_json::ADSBJsonMessage;
+use crate::aircraft_json::{fill_entry_defaults, SnapshotEntry};
+use crate::MessageResult;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a complete API v2 response, as served by ADSBexchange, adsb.lol and other readsb based aggregators, and will not consume the source.
+pub trait NewApiV2Response {
+    fn to_api_v2(&self) -> MessageResult<ApiV2Response>;
+}
+
+/// Implementing `.to_api_v2()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewApiV2Response for String {
+    fn to_api_v2(&self) -> MessageResult<ApiV2Response> {
+        serde_json::from_str(self)
+    }
+}
+
+/// Supporting `.to_api_v2()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewApiV2Response for str {
+    fn to_api_v2(&self) -> MessageResult<ApiV2Response> {
+        serde_json::from_str(self)
+    }
+}
+
+/// A response of the community API v2, such as `/v2/icao/<hex>` or `/v2/point/<lat>/<lon>/<radius>`.
+///
+/// The entries follow the readsb schema without `now`, and leave out `r` and `t` for aircraft missing from the database,
+/// so those are filled from the envelope or with defaults.
+/// Entries whose position is older than 60 seconds only carry it in `lastPosition`, which is kept there and not treated as the current position,
+/// so those entries have no position.
+/// An entry that cannot be decoded is logged and left out rather than failing the whole response.
+#[derive(Debug, Clone, Default)]
+pub struct ApiV2Response {
+    pub ac: Vec<ADSBJsonMessage>, // aircraft matching the query
+    pub msg: String,              // status message, "No error" on success
+    pub now: u64,                 // Unix timestamp of the response, milliseconds
+    pub total: u64,               // number of aircraft matching the query
+    pub ctime: u64,               // Unix timestamp the cached data was built, milliseconds
+    pub ptime: f64,               // processing time, milliseconds
+}
+
+#[derive(Deserialize)]
+struct RawApiV2Response {
+    #[serde(default)]
+    ac: Vec<Map<String, Value>>,
+    #[serde(default)]
+    msg: String,
+    now: u64,
+    #[serde(default)]
+    total: u64,
+    #[serde(default)]
+    ctime: u64,
+    #[serde(default)]
+    ptime: f64,
+}
+
+impl<'de> Deserialize<'de> for ApiV2Response {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw_response: RawApiV2Response = RawApiV2Response::deserialize(deserializer)?;
+        let now: f64 = raw_response.now as f64 / 1000.0;
+        let mut ac: Vec<ADSBJsonMessage> = Vec::with_capacity(raw_response.ac.len());
+        for mut entry in raw_response.ac {
+            fill_entry_defaults(&mut entry, now);
+            let hex: Option<Value> = entry.get("hex").cloned();
+            match serde_json::from_value(Value::Object(entry)) {
+                Err(entry_error) => debug!("Skipping aircraft {:?}: {}", hex, entry_error),
+                Ok(message) => ac.push(message),
+            }
+        }
+        Ok(ApiV2Response {
+            ac,
+            msg: raw_response.msg,
+            now: raw_response.now,
+            total: raw_response.total,
+            ctime: raw_response.ctime,
+            ptime: raw_response.ptime,
+        })
+    }
+}
+
+impl Serialize for ApiV2Response {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let entries: Vec<SnapshotEntry> = self.ac.iter().map(SnapshotEntry).collect();
+        let mut response = serializer.serialize_struct("ApiV2Response", 6)?;
+        response.serialize_field("ac", &entries)?;
+        response.serialize_field("msg", &self.msg)?;
+        response.serialize_field("now", &self.now)?;
+        response.serialize_field("total", &self.total)?;
+        response.serialize_field("ctime", &self.ctime)?;
+        response.serialize_field("ptime", &self.ptime)?;
+        response.end()
+    }
+}
+
+impl ApiV2Response {
+    /// Converts `ApiV2Response` to `String`.
+    pub fn to_string(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `ApiV2Response` to `String` and appends a `\n` to the end.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        match serde_json::to_string(self) {
+            Err(to_string_error) => Err(to_string_error),
+            Ok(string) => Ok(format!("{}\n", string)),
+        }
+    }
+
+    /// Converts `ApiV2Response` to a `String` encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
+        match self.to_string() {
+            Err(conversion_failed) => Err(conversion_failed),
+            Ok(string) => Ok(string.into_bytes()),
+        }
+    }
+}
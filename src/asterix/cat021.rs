@@ -0,0 +1,807 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude, SilType};
+use crate::asterix::{
+    encode_data_block, encode_record, split_data_blocks, time_of_day, timestamp_near,
+    AsterixReader, ItemFormat,
+};
+use crate::callsign::{decode_callsign, encode_callsign};
+use crate::{message_error, MessageResult};
+use serde::{Deserialize, Serialize};
+
+/// ASTERIX category of ADS-B target reports.
+pub const CAT021: u8 = 21;
+
+/// Subfields of I021/220 Met Information: wind speed, wind direction, temperature and turbulence.
+const MET_INFORMATION: [ItemFormat; 4] = [
+    ItemFormat::Fixed(2),
+    ItemFormat::Fixed(2),
+    ItemFormat::Fixed(2),
+    ItemFormat::Fixed(1),
+];
+
+/// Subfields of I021/110 Trajectory Intent: intent status and intent data points.
+const TRAJECTORY_INTENT: [ItemFormat; 2] = [ItemFormat::Variable, ItemFormat::Repetitive(15)];
+
+/// Subfields of I021/295 Data Ages, each a single octet age.
+const DATA_AGES: [ItemFormat; 23] = [ItemFormat::Fixed(1); 23];
+
+/// The standard CAT021 user application profile, edition 2.x, indexed by FRN less one.
+const CAT021_UAP: [ItemFormat; 49] = [
+    // 1 I021/010 Data Source Identification
+    ItemFormat::Fixed(2),
+    // 2 I021/040 Target Report Descriptor
+    ItemFormat::Variable,
+    // 3 I021/161 Track Number
+    ItemFormat::Fixed(2),
+    // 4 I021/015 Service Identification
+    ItemFormat::Fixed(1),
+    // 5 I021/071 Time of Applicability for Position
+    ItemFormat::Fixed(3),
+    // 6 I021/130 Position in WGS-84 Co-ordinates
+    ItemFormat::Fixed(6),
+    // 7 I021/131 High-Resolution Position in WGS-84 Co-ordinates
+    ItemFormat::Fixed(8),
+    // 8 I021/072 Time of Applicability for Velocity
+    ItemFormat::Fixed(3),
+    // 9 I021/150 Air Speed
+    ItemFormat::Fixed(2),
+    // 10 I021/151 True Air Speed
+    ItemFormat::Fixed(2),
+    // 11 I021/080 Target Address
+    ItemFormat::Fixed(3),
+    // 12 I021/073 Time of Message Reception of Position
+    ItemFormat::Fixed(3),
+    // 13 I021/074 Time of Message Reception of Position, High Precision
+    ItemFormat::Fixed(4),
+    // 14 I021/075 Time of Message Reception of Velocity
+    ItemFormat::Fixed(3),
+    // 15 I021/076 Time of Message Reception of Velocity, High Precision
+    ItemFormat::Fixed(4),
+    // 16 I021/140 Geometric Height
+    ItemFormat::Fixed(2),
+    // 17 I021/090 Quality Indicators
+    ItemFormat::Variable,
+    // 18 I021/210 MOPS Version
+    ItemFormat::Fixed(1),
+    // 19 I021/070 Mode 3/A Code
+    ItemFormat::Fixed(2),
+    // 20 I021/230 Roll Angle
+    ItemFormat::Fixed(2),
+    // 21 I021/145 Flight Level
+    ItemFormat::Fixed(2),
+    // 22 I021/152 Magnetic Heading
+    ItemFormat::Fixed(2),
+    // 23 I021/200 Target Status
+    ItemFormat::Fixed(1),
+    // 24 I021/155 Barometric Vertical Rate
+    ItemFormat::Fixed(2),
+    // 25 I021/157 Geometric Vertical Rate
+    ItemFormat::Fixed(2),
+    // 26 I021/160 Airborne Ground Vector
+    ItemFormat::Fixed(4),
+    // 27 I021/165 Track Angle Rate
+    ItemFormat::Fixed(2),
+    // 28 I021/077 Time of ASTERIX Report Transmission
+    ItemFormat::Fixed(3),
+    // 29 I021/170 Target Identification
+    ItemFormat::Fixed(6),
+    // 30 I021/020 Emitter Category
+    ItemFormat::Fixed(1),
+    // 31 I021/220 Met Information
+    ItemFormat::Compound(&MET_INFORMATION),
+    // 32 I021/146 Selected Altitude
+    ItemFormat::Fixed(2),
+    // 33 I021/148 Final State Selected Altitude
+    ItemFormat::Fixed(2),
+    // 34 I021/110 Trajectory Intent
+    ItemFormat::Compound(&TRAJECTORY_INTENT),
+    // 35 I021/016 Service Management
+    ItemFormat::Fixed(1),
+    // 36 I021/008 Aircraft Operational Status
+    ItemFormat::Fixed(1),
+    // 37 I021/271 Surface Capabilities and Characteristics
+    ItemFormat::Variable,
+    // 38 I021/132 Message Amplitude
+    ItemFormat::Fixed(1),
+    // 39 I021/250 Mode S MB Data
+    ItemFormat::Repetitive(8),
+    // 40 I021/260 ACAS Resolution Advisory Report
+    ItemFormat::Fixed(7),
+    // 41 I021/400 Receiver ID
+    ItemFormat::Fixed(1),
+    // 42 I021/295 Data Ages
+    ItemFormat::Compound(&DATA_AGES),
+    // 43
+    ItemFormat::Unused,
+    // 44
+    ItemFormat::Unused,
+    // 45
+    ItemFormat::Unused,
+    // 46
+    ItemFormat::Unused,
+    // 47
+    ItemFormat::Unused,
+    // 48 Reserved Expansion Field
+    ItemFormat::Explicit,
+    // 49 Special Purpose Field
+    ItemFormat::Explicit,
+];
+
+/// Seconds per least significant bit of the times of applicability.
+const TIME_RESOLUTION: f64 = 1.0 / 128.0;
+
+/// Degrees per least significant bit of the high resolution position.
+const HIGH_RESOLUTION_POSITION: f64 = 180.0 / 1_073_741_824.0;
+
+/// Degrees per least significant bit of the low resolution position.
+const LOW_RESOLUTION_POSITION: f64 = 180.0 / 8_388_608.0;
+
+/// Feet per least significant bit of the geometric height.
+const GEOMETRIC_HEIGHT_RESOLUTION: f32 = 6.25;
+
+/// Feet per minute per least significant bit of the vertical rates.
+const VERTICAL_RATE_RESOLUTION: f32 = 6.25;
+
+/// Knots per least significant bit of the ground speed, which is 2^-14 NM/s.
+const GROUND_SPEED_RESOLUTION: f32 = 3600.0 / 16384.0;
+
+/// Degrees per least significant bit of the track angle and magnetic heading.
+const ANGLE_RESOLUTION: f32 = 360.0 / 65536.0;
+
+/// Names of the emergency states, indexed by the priority status of I021/200.
+const PRIORITY_STATUSES: [&str; 7] = [
+    "none",
+    "general",
+    "lifeguard",
+    "minfuel",
+    "nordo",
+    "unlawful",
+    "downed",
+];
+
+/// ADS-B emitter categories, paired with their I021/020 emitter category.
+const EMITTER_CATEGORIES: [(&str, u8); 18] = [
+    ("A1", 1),
+    ("A2", 2),
+    ("A3", 3),
+    ("A4", 4),
+    ("A5", 5),
+    ("A6", 6),
+    ("A7", 10),
+    ("B1", 11),
+    ("B2", 12),
+    ("B3", 16),
+    ("B4", 15),
+    ("B6", 13),
+    ("B7", 14),
+    ("C1", 20),
+    ("C2", 21),
+    ("C3", 22),
+    ("C4", 23),
+    ("C5", 24),
+];
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be one or more complete ASTERIX data blocks, such as a UDP datagram, and will not consume the source.
+/// Blocks of other categories are skipped, as are CAT021 data items this library does not decode.
+pub trait NewCat021Records {
+    fn to_cat021(&self) -> MessageResult<Vec<Cat021Record>>;
+}
+
+/// Implementing `.to_cat021()` for the type `Vec<u8>`.
+///
+/// This does not consume the `Vec<u8>`.
+impl NewCat021Records for Vec<u8> {
+    fn to_cat021(&self) -> MessageResult<Vec<Cat021Record>> {
+        self.as_slice().to_cat021()
+    }
+}
+
+/// Supporting `.to_cat021()` for the type `[u8]`.
+///
+/// This does not consume the `[u8]`.
+impl NewCat021Records for [u8] {
+    fn to_cat021(&self) -> MessageResult<Vec<Cat021Record>> {
+        let mut records: Vec<Cat021Record> = Vec::new();
+        for block in split_data_blocks(self)? {
+            if block.category != CAT021 {
+                trace!("Skipping ASTERIX data block of category {}", block.category);
+                continue;
+            }
+            let mut reader: AsterixReader = AsterixReader::new(block.records);
+            while !reader.is_empty() {
+                records.push(Cat021Record::read(&mut reader)?);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// An ASTERIX CAT021 ADS-B target report, holding the data items this library decodes and encodes.
+///
+/// Times are seconds since midnight UTC, altitudes are in feet, speeds in knots and vertical rates in feet per minute.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Cat021Record {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_source: Option<Cat021DataSource>, // I021/010
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_report_descriptor: Option<Cat021TargetReportDescriptor>, // I021/040
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_of_applicability_position: Option<f64>, // I021/071
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Cat021Position>, // I021/131, or I021/130 when decoding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_of_applicability_velocity: Option<f64>, // I021/072
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_address: Option<u32>, // I021/080
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometric_height: Option<f32>, // I021/140, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality_indicators: Option<Cat021QualityIndicators>, // I021/090
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mops_version: Option<u8>, // I021/210, 0 DO-260, 1 DO-260A, 2 DO-260B
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode_3a: Option<String>, // I021/070, squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_level: Option<f32>, // I021/145, flight level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub magnetic_heading: Option<f32>, // I021/152, degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_status: Option<Cat021TargetStatus>, // I021/200
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barometric_vertical_rate: Option<f32>, // I021/155, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometric_vertical_rate: Option<f32>, // I021/157, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_vector: Option<Cat021GroundVector>, // I021/160
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_identification: Option<String>, // I021/170, callsign
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emitter_category: Option<u8>, // I021/020
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude: Option<Cat021SelectedAltitude>, // I021/146
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cat021DataSource {
+    pub sac: u8, // System Area Code
+    pub sic: u8, // System Identification Code
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cat021TargetReportDescriptor {
+    pub address_type: u8, // 0 ICAO, 1 duplicate, 2 surface vehicle, 3 anonymous
+    pub altitude_reporting_capability: u8, // 0 25 ft, 1 100 ft, 2 unknown, 3 invalid
+    pub range_check: bool, // range check passed
+    pub field_monitor: bool, // report from a field monitor rather than a target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_bit_set: Option<bool>, // the target reports being on the ground, from the first extension
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub simulated: Option<bool>, // simulated target report, from the first extension
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<bool>, // test target, from the first extension
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cat021Position {
+    pub lat: f64, // latitude
+    pub lon: f64, // longitude
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cat021QualityIndicators {
+    pub nac_v: u8, // Navigation Accuracy Category for Velocity
+    pub nic: u8,   // Navigation Integrity Category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_baro: Option<u8>, // Navigation Integrity Category for Barometric Altitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil: Option<u8>, // Source Integrity Level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nac_p: Option<u8>, // Navigation Accuracy Category for Position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil_supplement: Option<u8>, // 0 SIL is per hour, 1 SIL is per sample
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sda: Option<u8>, // System Design Assurance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gva: Option<u8>, // Geometric Vertical Accuracy
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cat021TargetStatus {
+    pub intent_change: bool,      // intent change flag
+    pub lnav_not_engaged: bool,   // LNAV mode is not engaged
+    pub military_emergency: bool, // military emergency
+    pub priority_status: u8, // 0 no emergency, 1 general, 2 lifeguard, 3 minimum fuel, 4 no communications, 5 unlawful interference, 6 downed
+    pub surveillance_status: u8, // 0 no condition, 1 permanent alert, 2 temporary alert, 3 SPI set
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cat021GroundVector {
+    pub range_exceeded: bool, // the ground speed exceeds the range of the item
+    pub ground_speed: f32,    // knots
+    pub track_angle: f32,     // degrees clockwise from true north
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cat021SelectedAltitude {
+    pub source_available: bool, // the source is given
+    pub source: u8,             // 0 unknown, 1 aircraft altitude, 2 MCP/FCU, 3 FMS
+    pub altitude: i32,          // feet
+}
+
+impl Cat021Record {
+    /// Reads one record, skipping the data items this library does not decode.
+    fn read(reader: &mut AsterixReader) -> MessageResult<Cat021Record> {
+        let mut record: Cat021Record = Cat021Record::default();
+        for frn in reader.fspec()? {
+            let format: ItemFormat = match CAT021_UAP.get(frn - 1) {
+                None => {
+                    return Err(message_error(format!(
+                        "CAT021 record flags FRN {} beyond the UAP",
+                        frn
+                    )))
+                }
+                Some(format) => *format,
+            };
+            let item: &[u8] = reader.item(format)?;
+            match frn {
+                1 => {
+                    record.data_source = Some(Cat021DataSource {
+                        sac: item[0],
+                        sic: item[1],
+                    })
+                }
+                2 => {
+                    record.target_report_descriptor = Some(Cat021TargetReportDescriptor {
+                        address_type: item[0] >> 5,
+                        altitude_reporting_capability: (item[0] >> 3) & 0x03,
+                        range_check: item[0] & 0x04 != 0,
+                        field_monitor: item[0] & 0x02 != 0,
+                        ground_bit_set: item.get(1).map(|octet| octet & 0x40 != 0),
+                        simulated: item.get(1).map(|octet| octet & 0x20 != 0),
+                        test: item.get(1).map(|octet| octet & 0x10 != 0),
+                    })
+                }
+                5 => record.time_of_applicability_position = Some(decode_time(item)),
+                6 if record.position.is_none() => {
+                    record.position = Some(Cat021Position {
+                        lat: signed(item, 0, 3) as f64 * LOW_RESOLUTION_POSITION,
+                        lon: signed(item, 3, 3) as f64 * LOW_RESOLUTION_POSITION,
+                    })
+                }
+                7 => {
+                    record.position = Some(Cat021Position {
+                        lat: signed(item, 0, 4) as f64 * HIGH_RESOLUTION_POSITION,
+                        lon: signed(item, 4, 4) as f64 * HIGH_RESOLUTION_POSITION,
+                    })
+                }
+                8 => record.time_of_applicability_velocity = Some(decode_time(item)),
+                11 => record.target_address = Some(unsigned(item, 0, 3)),
+                16 => {
+                    record.geometric_height =
+                        Some(signed(item, 0, 2) as f32 * GEOMETRIC_HEIGHT_RESOLUTION)
+                }
+                17 => {
+                    record.quality_indicators = Some(Cat021QualityIndicators {
+                        nac_v: item[0] >> 5,
+                        nic: (item[0] >> 1) & 0x0f,
+                        nic_baro: item.get(1).map(|octet| octet >> 7),
+                        sil: item.get(1).map(|octet| (octet >> 5) & 0x03),
+                        nac_p: item.get(1).map(|octet| (octet >> 1) & 0x0f),
+                        sil_supplement: item.get(2).map(|octet| (octet >> 5) & 0x01),
+                        sda: item.get(2).map(|octet| (octet >> 3) & 0x03),
+                        gva: item.get(2).map(|octet| (octet >> 1) & 0x03),
+                    })
+                }
+                18 => record.mops_version = Some((item[0] >> 3) & 0x07),
+                19 => record.mode_3a = Some(format!("{:04o}", unsigned(item, 0, 2) & 0x0fff)),
+                21 => record.flight_level = Some(signed(item, 0, 2) as f32 / 4.0),
+                22 => {
+                    record.magnetic_heading = Some(unsigned(item, 0, 2) as f32 * ANGLE_RESOLUTION)
+                }
+                23 => {
+                    record.target_status = Some(Cat021TargetStatus {
+                        intent_change: item[0] & 0x80 != 0,
+                        lnav_not_engaged: item[0] & 0x40 != 0,
+                        military_emergency: item[0] & 0x20 != 0,
+                        priority_status: (item[0] >> 2) & 0x07,
+                        surveillance_status: item[0] & 0x03,
+                    })
+                }
+                24 => record.barometric_vertical_rate = decode_vertical_rate(item),
+                25 => record.geometric_vertical_rate = decode_vertical_rate(item),
+                26 => {
+                    record.ground_vector = Some(Cat021GroundVector {
+                        range_exceeded: item[0] & 0x80 != 0,
+                        ground_speed: (unsigned(item, 0, 2) & 0x7fff) as f32
+                            * GROUND_SPEED_RESOLUTION,
+                        track_angle: unsigned(item, 2, 2) as f32 * ANGLE_RESOLUTION,
+                    })
+                }
+                29 => record.target_identification = Some(decode_callsign(item)),
+                30 => record.emitter_category = Some(item[0]),
+                32 => {
+                    record.selected_altitude = Some(Cat021SelectedAltitude {
+                        source_available: item[0] & 0x80 != 0,
+                        source: (item[0] >> 5) & 0x03,
+                        altitude: ((unsigned(item, 0, 2) as i32) << 19 >> 19) * 25,
+                    })
+                }
+                _ => trace!("Skipping CAT021 FRN {}", frn),
+            }
+        }
+        Ok(record)
+    }
+
+    /// Encodes an `ADSBJsonMessage` as a CAT021 target report from the data source given by `sac` and `sic`.
+    /// Fields without a CAT021 data item, such as the registration and the signal level, are not carried.
+    ///
+    /// The times of applicability are derived from `now` less `seen_pos` for the position and `now` less `seen` for the velocity.
+    pub fn from_adsb_json(message: &ADSBJsonMessage, sac: u8, sic: u8) -> Cat021Record {
+        let ground: bool = matches!(message.alt_baro, Some(Altitude::Ground(_)));
+        let squawk: Option<u16> = message
+            .squawk
+            .as_deref()
+            .and_then(|squawk| u16::from_str_radix(squawk, 8).ok());
+        let priority_status: u8 = message
+            .emergency
+            .as_deref()
+            .and_then(|emergency| {
+                PRIORITY_STATUSES
+                    .iter()
+                    .position(|status| *status == emergency)
+            })
+            .unwrap_or_default() as u8;
+        let selected_altitude: Option<Cat021SelectedAltitude> =
+            match (message.nav_altitude_mcp, message.nav_altitude_fms) {
+                (Some(altitude), _) => Some(Cat021SelectedAltitude {
+                    source_available: true,
+                    source: 2,
+                    altitude,
+                }),
+                (None, Some(altitude)) => Some(Cat021SelectedAltitude {
+                    source_available: true,
+                    source: 3,
+                    altitude,
+                }),
+                (None, None) => None,
+            };
+        Cat021Record {
+            data_source: Some(Cat021DataSource { sac, sic }),
+            target_report_descriptor: Some(Cat021TargetReportDescriptor {
+                address_type: match message.hex.starts_with('~') {
+                    true => 3,
+                    false => 0,
+                },
+                altitude_reporting_capability: 0,
+                range_check: false,
+                field_monitor: false,
+                ground_bit_set: Some(ground),
+                simulated: Some(false),
+                test: Some(false),
+            }),
+            time_of_applicability_position: message
+                .seen_pos
+                .map(|seen_pos| time_of_day(message.now - seen_pos)),
+            position: message.position().map(|(lat, lon)| Cat021Position {
+                lat: lat as f64,
+                lon: lon as f64,
+            }),
+            time_of_applicability_velocity: Some(time_of_day(message.now - message.seen)),
+            target_address: u32::from_str_radix(message.hex.trim_start_matches('~'), 16).ok(),
+            geometric_height: message.alt_geom.map(|altitude| altitude as f32),
+            quality_indicators: Some(Cat021QualityIndicators {
+                nac_v: message.nac_v.unwrap_or_default().clamp(0, 7) as u8,
+                nic: message.nic.unwrap_or_default().clamp(0, 15) as u8,
+                nic_baro: Some(message.nic_baro.unwrap_or_default().clamp(0, 1) as u8),
+                sil: Some(message.sil.unwrap_or_default().clamp(0, 3) as u8),
+                nac_p: Some(message.nac_p.unwrap_or_default().clamp(0, 15) as u8),
+                sil_supplement: Some(match message.sil_type {
+                    SilType::PerSample => 1,
+                    _ => 0,
+                }),
+                sda: Some(message.sda.unwrap_or_default().clamp(0, 3) as u8),
+                gva: Some(message.gva.unwrap_or_default().clamp(0, 3) as u8),
+            }),
+            mops_version: message.version.map(|version| version.clamp(0, 7) as u8),
+            mode_3a: squawk.map(|squawk| format!("{:04o}", squawk)),
+            flight_level: match &message.alt_baro {
+                Some(Altitude::I32(altitude)) => Some(*altitude as f32 / 100.0),
+                Some(Altitude::Ground(_)) | None => None,
+            },
+            magnetic_heading: message.mag_heading,
+            target_status: Some(Cat021TargetStatus {
+                intent_change: false,
+                lnav_not_engaged: false,
+                military_emergency: false,
+                priority_status,
+                surveillance_status: match message.spi {
+                    Some(1) => 3,
+                    _ => 0,
+                },
+            }),
+            barometric_vertical_rate: message.baro_rate.map(|rate| rate as f32),
+            geometric_vertical_rate: message.geom_rate.map(|rate| rate as f32),
+            ground_vector: match (message.gs, message.track) {
+                (Some(ground_speed), Some(track_angle)) => Some(Cat021GroundVector {
+                    range_exceeded: false,
+                    ground_speed,
+                    track_angle,
+                }),
+                _ => None,
+            },
+            target_identification: message.flight.clone(),
+            emitter_category: message.category.as_deref().and_then(|category| {
+                EMITTER_CATEGORIES
+                    .iter()
+                    .find(|(name, _)| *name == category)
+                    .map(|(_, number)| *number)
+            }),
+            selected_altitude,
+        }
+    }
+
+    /// Converts `Cat021Record` to an `ADSBJsonMessage`, resolving the times of applicability against `now`, a Unix timestamp in seconds.
+    ///
+    /// Records without a target address return an error, and records without a position leave the position fields empty.
+    pub fn to_adsb_json(&self, now: f64) -> MessageResult<ADSBJsonMessage> {
+        let target_address: u32 = match self.target_address {
+            None => return Err(message_error("CAT021 record has no target address")),
+            Some(target_address) => target_address,
+        };
+        let descriptor: Cat021TargetReportDescriptor =
+            self.target_report_descriptor.unwrap_or_default();
+        let quality: Cat021QualityIndicators = self.quality_indicators.unwrap_or_default();
+        let status: Option<Cat021TargetStatus> = self.target_status;
+        let position_time: Option<f64> = self
+            .time_of_applicability_position
+            .map(|time| timestamp_near(time, now));
+        let last_time: Option<f64> = match (
+            position_time,
+            self.time_of_applicability_velocity
+                .map(|time| timestamp_near(time, now)),
+        ) {
+            (Some(position_time), Some(velocity_time)) => Some(position_time.max(velocity_time)),
+            (position_time, velocity_time) => position_time.or(velocity_time),
+        };
+        let alt_baro: Option<Altitude> = match (descriptor.ground_bit_set, self.flight_level) {
+            (Some(true), _) => Some(Altitude::Ground("ground".to_string())),
+            (_, Some(flight_level)) => Some(Altitude::I32((flight_level * 100.0).round() as i32)),
+            (_, None) => None,
+        };
+        Ok(ADSBJsonMessage {
+            now,
+            hex: match descriptor.address_type {
+                0 => format!("{:06x}", target_address),
+                _ => format!("~{:06x}", target_address),
+            },
+            adsb_type: match descriptor.address_type {
+                0 => "adsb_icao".to_string(),
+                _ => "adsb_other".to_string(),
+            },
+            flight: self.target_identification.clone(),
+            aircraft_registration: String::new(),
+            alt_baro,
+            alt_geom: self
+                .geometric_height
+                .map(|altitude| altitude.round() as i32),
+            gs: self.ground_vector.map(|vector| vector.ground_speed),
+            track: self.ground_vector.map(|vector| vector.track_angle),
+            mag_heading: self.magnetic_heading,
+            baro_rate: self
+                .barometric_vertical_rate
+                .map(|rate| rate.round() as i32),
+            geom_rate: self.geometric_vertical_rate.map(|rate| rate.round() as i32),
+            squawk: self.mode_3a.clone(),
+            emergency: status.map(|status| {
+                PRIORITY_STATUSES
+                    .get(status.priority_status as usize)
+                    .unwrap_or(&"reserved")
+                    .to_string()
+            }),
+            category: self.emitter_category.and_then(|number| {
+                EMITTER_CATEGORIES
+                    .iter()
+                    .find(|(_, candidate)| *candidate == number)
+                    .map(|(name, _)| name.to_string())
+            }),
+            nav_altitude_mcp: self
+                .selected_altitude
+                .filter(|selected| selected.source != 3)
+                .map(|selected| selected.altitude),
+            nav_altitude_fms: self
+                .selected_altitude
+                .filter(|selected| selected.source == 3)
+                .map(|selected| selected.altitude),
+            lat: self.position.map(|position| position.lat as f32),
+            lon: self.position.map(|position| position.lon as f32),
+            nic: self.position.map(|_| quality.nic as i32),
+            seen_pos: self.position.and(position_time).map(|time| now - time),
+            seen: last_time.map(|time| now - time).unwrap_or_default(),
+            version: self.mops_version.map(|version| version as i32),
+            nic_baro: quality.nic_baro.map(|value| value as i8),
+            nac_p: quality.nac_p.map(|value| value as i8),
+            nac_v: Some(quality.nac_v as i8),
+            sil: quality.sil.map(|value| value as i8),
+            sil_type: match quality.sil_supplement {
+                Some(0) => SilType::PerHour,
+                Some(_) => SilType::PerSample,
+                None => SilType::Unknown,
+            },
+            gva: quality.gva.map(|value| value as i8),
+            sda: quality.sda.map(|value| value as i8),
+            spi: status.map(|status| (status.surveillance_status == 3) as i8),
+            messages: 1,
+            ..Default::default()
+        })
+    }
+
+    /// Converts `Cat021Record` to a record, an FSPEC followed by its data items, without a data block header.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_record_bytes(&self) -> Vec<u8> {
+        let mut items: Vec<Option<Vec<u8>>> = vec![None; CAT021_UAP.len()];
+        items[0] = self.data_source.map(|source| vec![source.sac, source.sic]);
+        items[1] = self.target_report_descriptor.map(|descriptor| {
+            let first: u8 = (descriptor.address_type & 0x07) << 5
+                | (descriptor.altitude_reporting_capability & 0x03) << 3
+                | (descriptor.range_check as u8) << 2
+                | (descriptor.field_monitor as u8) << 1;
+            match (
+                descriptor.ground_bit_set,
+                descriptor.simulated,
+                descriptor.test,
+            ) {
+                (None, None, None) => vec![first],
+                (ground_bit_set, simulated, test) => vec![
+                    first | 0x01,
+                    (ground_bit_set.unwrap_or_default() as u8) << 6
+                        | (simulated.unwrap_or_default() as u8) << 5
+                        | (test.unwrap_or_default() as u8) << 4,
+                ],
+            }
+        });
+        items[4] = self.time_of_applicability_position.map(encode_time);
+        items[6] = self.position.map(|position| {
+            let mut item: Vec<u8> = ((position.lat / HIGH_RESOLUTION_POSITION).round() as i32)
+                .to_be_bytes()
+                .to_vec();
+            item.extend_from_slice(
+                &((position.lon / HIGH_RESOLUTION_POSITION).round() as i32).to_be_bytes(),
+            );
+            item
+        });
+        items[7] = self.time_of_applicability_velocity.map(encode_time);
+        items[10] = self
+            .target_address
+            .map(|address| address.to_be_bytes()[1..].to_vec());
+        items[15] = self.geometric_height.map(|height| {
+            ((height / GEOMETRIC_HEIGHT_RESOLUTION).round() as i16)
+                .to_be_bytes()
+                .to_vec()
+        });
+        items[16] = self.quality_indicators.map(|quality| {
+            let mut item: Vec<u8> = vec![(quality.nac_v & 0x07) << 5 | (quality.nic & 0x0f) << 1];
+            if quality.nic_baro.is_some() || quality.sil.is_some() || quality.nac_p.is_some() {
+                item.push(
+                    (quality.nic_baro.unwrap_or_default() & 0x01) << 7
+                        | (quality.sil.unwrap_or_default() & 0x03) << 5
+                        | (quality.nac_p.unwrap_or_default() & 0x0f) << 1,
+                );
+            }
+            if quality.sil_supplement.is_some() || quality.sda.is_some() || quality.gva.is_some() {
+                if item.len() == 1 {
+                    item.push(0);
+                }
+                item.push(
+                    (quality.sil_supplement.unwrap_or_default() & 0x01) << 5
+                        | (quality.sda.unwrap_or_default() & 0x03) << 3
+                        | (quality.gva.unwrap_or_default() & 0x03) << 1,
+                );
+            }
+            let last: usize = item.len() - 1;
+            for octet in item.iter_mut().take(last) {
+                *octet |= 0x01;
+            }
+            item
+        });
+        items[17] = self
+            .mops_version
+            .map(|version| vec![(version & 0x07) << 3 | 0x02]);
+        items[18] = self.mode_3a.as_deref().and_then(|squawk| {
+            u16::from_str_radix(squawk, 8)
+                .ok()
+                .map(|squawk| (squawk & 0x0fff).to_be_bytes().to_vec())
+        });
+        items[20] = self
+            .flight_level
+            .map(|flight_level| ((flight_level * 4.0).round() as i16).to_be_bytes().to_vec());
+        items[21] = self.magnetic_heading.map(encode_angle);
+        items[22] = self.target_status.map(|status| {
+            vec![
+                (status.intent_change as u8) << 7
+                    | (status.lnav_not_engaged as u8) << 6
+                    | (status.military_emergency as u8) << 5
+                    | (status.priority_status & 0x07) << 2
+                    | (status.surveillance_status & 0x03),
+            ]
+        });
+        items[23] = self.barometric_vertical_rate.map(encode_vertical_rate);
+        items[24] = self.geometric_vertical_rate.map(encode_vertical_rate);
+        items[25] = self.ground_vector.map(|vector| {
+            let speed: u32 = (vector.ground_speed / GROUND_SPEED_RESOLUTION).round() as u32;
+            let range_exceeded: bool = vector.range_exceeded || speed > 0x7fff;
+            let mut item: Vec<u8> = ((range_exceeded as u16) << 15 | speed.min(0x7fff) as u16)
+                .to_be_bytes()
+                .to_vec();
+            item.extend_from_slice(&encode_angle(vector.track_angle));
+            item
+        });
+        items[28] = self
+            .target_identification
+            .as_deref()
+            .map(|callsign| encode_callsign(callsign).to_vec());
+        items[29] = self.emitter_category.map(|category| vec![category]);
+        items[31] = self.selected_altitude.map(|selected| {
+            let altitude: u16 = ((selected.altitude / 25).clamp(-4096, 4095) as u16) & 0x1fff;
+            ((selected.source_available as u16) << 15
+                | ((selected.source & 0x03) as u16) << 13
+                | altitude)
+                .to_be_bytes()
+                .to_vec()
+        });
+        encode_record(&items)
+    }
+
+    /// Converts records to a single CAT021 data block, ready to send as a UDP datagram.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_data_block(records: &[Cat021Record]) -> MessageResult<Vec<u8>> {
+        let records: Vec<Vec<u8>> = records.iter().map(Cat021Record::to_record_bytes).collect();
+        encode_data_block(CAT021, &records)
+    }
+}
+
+fn unsigned(item: &[u8], start: usize, length: usize) -> u32 {
+    item[start..start + length]
+        .iter()
+        .fold(0u32, |value, octet| value << 8 | *octet as u32)
+}
+
+fn signed(item: &[u8], start: usize, length: usize) -> i32 {
+    let shift: usize = 32 - length * 8;
+    ((unsigned(item, start, length) << shift) as i32) >> shift
+}
+
+fn decode_time(item: &[u8]) -> f64 {
+    unsigned(item, 0, 3) as f64 * TIME_RESOLUTION
+}
+
+fn encode_time(time: f64) -> Vec<u8> {
+    let time: u32 = ((time / TIME_RESOLUTION).round() as u32) & 0x00ff_ffff;
+    time.to_be_bytes()[1..].to_vec()
+}
+
+fn decode_vertical_rate(item: &[u8]) -> Option<f32> {
+    match item[0] & 0x80 != 0 {
+        true => None,
+        false => {
+            Some(((unsigned(item, 0, 2) as i32) << 17 >> 17) as f32 * VERTICAL_RATE_RESOLUTION)
+        }
+    }
+}
+
+fn encode_vertical_rate(rate: f32) -> Vec<u8> {
+    let rate: i32 = (rate / VERTICAL_RATE_RESOLUTION).round() as i32;
+    let item: u16 = match rate {
+        -16384..=16383 => rate as u16 & 0x7fff,
+        _ => 0x8000 | (rate.clamp(-16384, 16383) as u16 & 0x7fff),
+    };
+    item.to_be_bytes().to_vec()
+}
+
+fn encode_angle(angle: f32) -> Vec<u8> {
+    (((angle.rem_euclid(360.0) / ANGLE_RESOLUTION).round() as u32 & 0xffff) as u16)
+        .to_be_bytes()
+        .to_vec()
+}
@@ -0,0 +1,226 @@
+pub mod cat021;
+
+use crate::{message_error, MessageResult};
+
+/// Size of the category and length octets that start every data block.
+const DATA_BLOCK_HEADER_LENGTH: usize = 3;
+
+/// Seconds in a day, ASTERIX times count the seconds since midnight UTC.
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// How the length of a data item, or of a subfield of a compound data item, is found.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ItemFormat {
+    Fixed(usize),                    // always this many octets
+    Variable,                        // octets until one without the FX bit set
+    Repetitive(usize), // a repetition count octet, then that many items of this many octets
+    Explicit,          // a length octet that counts itself
+    Compound(&'static [ItemFormat]), // a variable primary subfield then the subfields it flags, in order
+    Unused,                          // not defined in the UAP
+}
+
+/// An EUROCONTROL ASTERIX data block, split into its category and the bytes of its records.
+///
+/// A data block is a category octet, a two octet length covering the whole block, and one or more records.
+/// Each record starts with a field specification (FSPEC) whose bits, in field reference number (FRN) order,
+/// say which data items of the category's user application profile (UAP) follow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsterixDataBlock<'a> {
+    pub category: u8,      // data category, such as 21
+    pub records: &'a [u8], // the records, without the category and length octets
+}
+
+/// Splits a buffer of one or more concatenated data blocks into their categories and records.
+pub fn split_data_blocks(data: &[u8]) -> MessageResult<Vec<AsterixDataBlock<'_>>> {
+    let mut blocks: Vec<AsterixDataBlock> = Vec::new();
+    let mut offset: usize = 0;
+    while offset < data.len() {
+        if data.len() - offset < DATA_BLOCK_HEADER_LENGTH {
+            return Err(message_error(format!(
+                "Truncated ASTERIX data block header at offset {}",
+                offset
+            )));
+        }
+        let length: usize = u16::from_be_bytes([data[offset + 1], data[offset + 2]]) as usize;
+        if length < DATA_BLOCK_HEADER_LENGTH || offset + length > data.len() {
+            return Err(message_error(format!(
+                "ASTERIX data block at offset {} has length {} but {} bytes remain",
+                offset,
+                length,
+                data.len() - offset
+            )));
+        }
+        blocks.push(AsterixDataBlock {
+            category: data[offset],
+            records: &data[offset + DATA_BLOCK_HEADER_LENGTH..offset + length],
+        });
+        offset += length;
+    }
+    Ok(blocks)
+}
+
+/// Builds a data block from encoded records.
+///
+/// The length octets can count at most 65535 bytes, so callers sending many records should split them across blocks.
+pub fn encode_data_block(category: u8, records: &[Vec<u8>]) -> MessageResult<Vec<u8>> {
+    let length: usize = DATA_BLOCK_HEADER_LENGTH + records.iter().map(Vec::len).sum::<usize>();
+    if length > u16::MAX as usize {
+        return Err(message_error(format!(
+            "ASTERIX data block of {} bytes is too long",
+            length
+        )));
+    }
+    let mut block: Vec<u8> = Vec::with_capacity(length);
+    block.push(category);
+    block.extend_from_slice(&(length as u16).to_be_bytes());
+    for record in records {
+        block.extend_from_slice(record);
+    }
+    Ok(block)
+}
+
+/// Builds a record from its data items, indexed by FRN less one, writing the FSPEC for the items present.
+pub(crate) fn encode_record(items: &[Option<Vec<u8>>]) -> Vec<u8> {
+    let last_item: usize = match items.iter().rposition(Option::is_some) {
+        None => return vec![0],
+        Some(last_item) => last_item,
+    };
+    let mut fspec: Vec<u8> = vec![0; last_item / 7 + 1];
+    let fspec_length: usize = fspec.len();
+    for (index, item) in items.iter().enumerate().take(last_item + 1) {
+        if item.is_some() {
+            fspec[index / 7] |= 0x80 >> (index % 7);
+        }
+    }
+    for octet in fspec.iter_mut().take(fspec_length - 1) {
+        *octet |= 0x01;
+    }
+    let mut record: Vec<u8> = fspec;
+    for item in items.iter().flatten() {
+        record.extend_from_slice(item);
+    }
+    record
+}
+
+/// Reads records and their data items from the records of a data block.
+pub(crate) struct AsterixReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> AsterixReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        AsterixReader { data, offset: 0 }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn take(&mut self, length: usize) -> MessageResult<&'a [u8]> {
+        if self.data.len() - self.offset < length {
+            return Err(message_error(format!(
+                "Truncated ASTERIX record, needed {} bytes at offset {} of {}",
+                length,
+                self.offset,
+                self.data.len()
+            )));
+        }
+        let bytes: &[u8] = &self.data[self.offset..self.offset + length];
+        self.offset += length;
+        Ok(bytes)
+    }
+
+    /// Reads octets until one without the FX bit set, as used by FSPECs and variable length items.
+    fn variable(&mut self) -> MessageResult<&'a [u8]> {
+        let start: usize = self.offset;
+        loop {
+            let octet: u8 = self.take(1)?[0];
+            if octet & 0x01 == 0 {
+                return Ok(&self.data[start..self.offset]);
+            }
+        }
+    }
+
+    /// Reads an FSPEC and returns the FRNs it flags, starting from 1.
+    pub(crate) fn fspec(&mut self) -> MessageResult<Vec<usize>> {
+        Ok(flagged_subfields(self.variable()?)
+            .into_iter()
+            .map(|index| index + 1)
+            .collect())
+    }
+
+    /// Reads a data item of the given format, returning all of its octets.
+    pub(crate) fn item(&mut self, format: ItemFormat) -> MessageResult<&'a [u8]> {
+        let start: usize = self.offset;
+        match format {
+            ItemFormat::Fixed(length) => {
+                self.take(length)?;
+            }
+            ItemFormat::Variable => {
+                self.variable()?;
+            }
+            ItemFormat::Repetitive(length) => {
+                let repetitions: usize = self.take(1)?[0] as usize;
+                self.take(repetitions * length)?;
+            }
+            ItemFormat::Explicit => {
+                let length: usize = self.take(1)?[0] as usize;
+                if length == 0 {
+                    return Err(message_error("ASTERIX explicit item has a length of 0"));
+                }
+                self.take(length - 1)?;
+            }
+            ItemFormat::Compound(subfields) => {
+                for index in flagged_subfields(self.variable()?) {
+                    match subfields.get(index) {
+                        None | Some(ItemFormat::Unused) => {
+                            return Err(message_error(format!(
+                                "ASTERIX compound item flags undefined subfield {}",
+                                index + 1
+                            )))
+                        }
+                        Some(subfield) => {
+                            self.item(*subfield)?;
+                        }
+                    }
+                }
+            }
+            ItemFormat::Unused => {
+                return Err(message_error(
+                    "ASTERIX record flags an item not defined in the UAP",
+                ))
+            }
+        }
+        Ok(&self.data[start..self.offset])
+    }
+}
+
+/// Returns the indexes, from 0, of the bits set in a chain of FX terminated octets, skipping the FX bits.
+fn flagged_subfields(octets: &[u8]) -> Vec<usize> {
+    let mut flagged: Vec<usize> = Vec::new();
+    for (octet_index, octet) in octets.iter().enumerate() {
+        for bit in 0..7 {
+            if octet & (0x80 >> bit) != 0 {
+                flagged.push(octet_index * 7 + bit);
+            }
+        }
+    }
+    flagged
+}
+
+/// Converts a Unix timestamp in seconds, such as `now`, to the seconds since midnight UTC.
+pub(crate) fn time_of_day(timestamp: f64) -> f64 {
+    timestamp.rem_euclid(SECONDS_PER_DAY)
+}
+
+/// Converts seconds since midnight UTC to the Unix timestamp nearest to `now`, so times just before or after midnight resolve to the right day.
+pub(crate) fn timestamp_near(time_of_day: f64, now: f64) -> f64 {
+    let midnight: f64 = now - now.rem_euclid(SECONDS_PER_DAY);
+    let timestamp: f64 = midnight + time_of_day;
+    match timestamp - now {
+        difference if difference > SECONDS_PER_DAY / 2.0 => timestamp - SECONDS_PER_DAY,
+        difference if difference < -SECONDS_PER_DAY / 2.0 => timestamp + SECONDS_PER_DAY,
+        _ => timestamp,
+    }
+}
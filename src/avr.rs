@@ -0,0 +1,71 @@
+use crate::beast::{BeastMessage, BeastMessageType};
+use crate::{decode_hex, message_error, MessageResult};
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single AVR line, either `*<frame>;` or the timestamped `@<timestamp><frame>;`, and will not consume the source.
+///
+/// AVR lines carry no signal level, so the decoded `BeastMessage` has a `signal_level` of 0.
+/// Lines without a timestamp are decoded with a `timestamp` of 0.
+pub trait NewAvrMessage {
+    fn to_avr(&self) -> MessageResult<BeastMessage>;
+}
+
+/// Implementing `.to_avr()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewAvrMessage for String {
+    fn to_avr(&self) -> MessageResult<BeastMessage> {
+        self.as_str().to_avr()
+    }
+}
+
+/// Supporting `.to_avr()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewAvrMessage for str {
+    fn to_avr(&self) -> MessageResult<BeastMessage> {
+        let line: &str = self.trim();
+        let body: &str = match line.strip_suffix(';') {
+            None => {
+                return Err(message_error(format!(
+                    "AVR line must end with ';': {}",
+                    line
+                )))
+            }
+            Some(body) => body,
+        };
+        let (timestamp, frame) = match body.chars().next() {
+            Some('*') => (0, &body[1..]),
+            Some('@') => match (body.get(1..13), body.get(13..)) {
+                (Some(timestamp), Some(frame)) => match decode_hex(timestamp) {
+                    Err(_) => {
+                        return Err(message_error(format!("Invalid AVR timestamp: {}", line)))
+                    }
+                    Ok(timestamp) => (
+                        timestamp
+                            .iter()
+                            .fold(0, |timestamp, byte| (timestamp << 8) | *byte as u64),
+                        frame,
+                    ),
+                },
+                _ => return Err(message_error(format!("Truncated AVR line: {}", line))),
+            },
+            _ => return Err(message_error(format!("Unrecognised AVR line: {}", line))),
+        };
+        let data: Vec<u8> = decode_hex(frame)?;
+        match BeastMessageType::from_data_length(data.len()) {
+            None => Err(message_error(format!(
+                "AVR frame has an invalid length of {} bytes: {}",
+                data.len(),
+                line
+            ))),
+            Some(message_type) => Ok(BeastMessage {
+                message_type,
+                timestamp,
+                signal_level: 0,
+                data,
+            }),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::adsb_json::ADSBJsonMessage;
+use crate::modes::NewModeSMessage;
+use crate::{message_error, MessageResult};
+use serde::{Deserialize, Serialize};
+
+/// Byte that starts every Beast frame, and is doubled whenever it appears inside a frame.
+pub const BEAST_ESCAPE: u8 = 0x1a;
+
+/// Rate of the Beast timestamp counter in Hz.
+pub const BEAST_CLOCK_HZ: f64 = 12_000_000.0;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single, complete Beast frame starting with the `0x1a` escape byte, and will not consume the source.
+pub trait NewBeastMessage {
+    fn to_beast(&self) -> MessageResult<BeastMessage>;
+}
+
+/// Implementing `.to_beast()` for the type `Vec<u8>`.
+///
+/// This does not consume the `Vec<u8>`.
+impl NewBeastMessage for Vec<u8> {
+    fn to_beast(&self) -> MessageResult<BeastMessage> {
+        self.as_slice().to_beast()
+    }
+}
+
+/// Supporting `.to_beast()` for the type `[u8]`.
+///
+/// This does not consume the `[u8]`.
+impl NewBeastMessage for [u8] {
+    fn to_beast(&self) -> MessageResult<BeastMessage> {
+        match BeastMessage::decode_frame(self)? {
+            None => Err(message_error("Incomplete Beast frame")),
+            Some((message, consumed)) if consumed == self.len() => Ok(message),
+            Some((_, consumed)) => Err(message_error(format!(
+                "Unexpected data after Beast frame at byte {}",
+                consumed
+            ))),
+        }
+    }
+}
+
+/// Decodes every complete Beast frame found in `buffer`, such as the data read from a readsb port 30005 connection.
+///
+/// Returns the decoded messages and the number of bytes consumed.
+/// Bytes after the consumed count belong to a frame that has not been fully received yet and should be kept for the next read.
+/// Data that cannot be decoded is skipped until the next frame start.
+pub fn decode_beast_frames(buffer: &[u8]) -> (Vec<BeastMessage>, usize) {
+    let mut messages: Vec<BeastMessage> = Vec::new();
+    let mut position: usize = 0;
+    while position < buffer.len() {
+        if buffer[position] != BEAST_ESCAPE {
+            position += 1;
+            continue;
+        }
+        match BeastMessage::decode_frame(&buffer[position..]) {
+            Ok(None) => break,
+            Ok(Some((message, consumed))) => {
+                messages.push(message);
+                position += consumed;
+            }
+            Err(frame_error) => {
+                debug!("Skipping Beast data at byte {}: {}", position, frame_error);
+                position += 1;
+            }
+        }
+    }
+    (messages, position)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BeastMessage {
+    pub message_type: BeastMessageType,
+    pub timestamp: u64,   // 48 bit count of the receiver's 12 MHz clock
+    pub signal_level: u8, // RSSI byte, linear signal amplitude
+    pub data: Vec<u8>,    // Mode A/C or Mode-S frame
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeastMessageType {
+    #[serde(rename = "mode_ac")]
+    ModeAC,
+    #[serde(rename = "mode_s_short")]
+    ModeSShort,
+    #[serde(rename = "mode_s_long")]
+    ModeSLong,
+}
+
+impl BeastMessageType {
+    /// Returns the type matching the byte following the escape byte of a frame.
+    pub fn from_type_byte(type_byte: u8) -> Option<Self> {
+        match type_byte {
+            b'1' => Some(BeastMessageType::ModeAC),
+            b'2' => Some(BeastMessageType::ModeSShort),
+            b'3' => Some(BeastMessageType::ModeSLong),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte identifying this type on the wire.
+    pub fn type_byte(&self) -> u8 {
+        match self {
+            BeastMessageType::ModeAC => b'1',
+            BeastMessageType::ModeSShort => b'2',
+            BeastMessageType::ModeSLong => b'3',
+        }
+    }
+
+    /// Returns the type carrying frame data of `length` bytes.
+    pub fn from_data_length(length: usize) -> Option<Self> {
+        match length {
+            2 => Some(BeastMessageType::ModeAC),
+            7 => Some(BeastMessageType::ModeSShort),
+            14 => Some(BeastMessageType::ModeSLong),
+            _ => None,
+        }
+    }
+
+    /// Returns the length in bytes of the frame data carried by this type.
+    pub fn data_length(&self) -> usize {
+        match self {
+            BeastMessageType::ModeAC => 2,
+            BeastMessageType::ModeSShort => 7,
+            BeastMessageType::ModeSLong => 14,
+        }
+    }
+}
+
+impl BeastMessage {
+    /// Decodes the Beast frame at the start of `buffer`.
+    ///
+    /// Returns `None` if the buffer ends before the frame does, otherwise the message and the number of bytes it used.
+    pub fn decode_frame(buffer: &[u8]) -> MessageResult<Option<(BeastMessage, usize)>> {
+        match buffer.first() {
+            None => return Ok(None),
+            Some(&BEAST_ESCAPE) => {}
+            Some(other) => {
+                return Err(message_error(format!(
+                    "Beast frame must start with 0x1a, found {:#04x}",
+                    other
+                )))
+            }
+        }
+        let message_type: BeastMessageType = match buffer.get(1) {
+            None => return Ok(None),
+            Some(type_byte) => match BeastMessageType::from_type_byte(*type_byte) {
+                None => {
+                    return Err(message_error(format!(
+                        "Unknown Beast frame type {:#04x}",
+                        type_byte
+                    )))
+                }
+                Some(message_type) => message_type,
+            },
+        };
+        let (body, consumed) = match unescape(buffer, 2, 7 + message_type.data_length())? {
+            None => return Ok(None),
+            Some(unescaped) => unescaped,
+        };
+        let timestamp: u64 = body[..6]
+            .iter()
+            .fold(0, |timestamp, byte| (timestamp << 8) | *byte as u64);
+        let message: BeastMessage = BeastMessage {
+            message_type,
+            timestamp,
+            signal_level: body[6],
+            data: body[7..].to_vec(),
+        };
+        trace!("Decoded Beast frame {:?}", &message);
+        Ok(Some((message, consumed)))
+    }
+
+    /// Converts `BeastMessage` to a Beast frame, escaping any `0x1a` bytes in the timestamp, signal level and data.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_beast_bytes(&self) -> MessageResult<Vec<u8>> {
+        trace!("Converting {:?} into a Beast frame", &self);
+        if self.data.len() != self.message_type.data_length() {
+            return Err(message_error(format!(
+                "Beast {:?} frames carry {} bytes of data, found {}",
+                self.message_type,
+                self.message_type.data_length(),
+                self.data.len()
+            )));
+        }
+        if self.timestamp >= 1 << 48 {
+            return Err(message_error(format!(
+                "Beast timestamp {:#x} does not fit in 48 bits",
+                self.timestamp
+            )));
+        }
+        let mut frame: Vec<u8> = Vec::with_capacity(2 * (9 + self.data.len()));
+        frame.push(BEAST_ESCAPE);
+        frame.push(self.message_type.type_byte());
+        let timestamp: [u8; 8] = self.timestamp.to_be_bytes();
+        let body = timestamp[2..]
+            .iter()
+            .chain(std::iter::once(&self.signal_level))
+            .chain(self.data.iter());
+        for byte in body {
+            frame.push(*byte);
+            if *byte == BEAST_ESCAPE {
+                frame.push(BEAST_ESCAPE);
+            }
+        }
+        Ok(frame)
+    }
+
+    /// Returns the signal level in dBFS, as reported by readsb in the `rssi` field.
+    pub fn signal_level_dbfs(&self) -> f32 {
+        let amplitude: f32 = self.signal_level as f32 / 255.0;
+        10.0 * (amplitude * amplitude + 1.125e-5).log10()
+    }
+
+    /// Returns the timestamp converted from clock ticks to seconds.
+    pub fn timestamp_seconds(&self) -> f64 {
+        self.timestamp as f64 / BEAST_CLOCK_HZ
+    }
+
+    /// Decodes the Mode-S frame in `data` and converts it to an `ADSBJsonMessage`, taking `rssi` from the signal level.
+    ///
+    /// `now` is the Unix timestamp the frame was received, as the Beast timestamp counts from an arbitrary point.
+    pub fn to_adsb_json(&self, now: f64) -> MessageResult<ADSBJsonMessage> {
+        let mut message: ADSBJsonMessage = self.to_modes()?.to_adsb_json(now);
+        message.rssi = self.signal_level_dbfs();
+        Ok(message)
+    }
+}
+
+/// Reads `count` bytes from `buffer` starting at `start`, collapsing doubled escape bytes.
+///
+/// Returns `None` if the buffer ends first, otherwise the bytes and the position after the last one read.
+fn unescape(buffer: &[u8], start: usize, count: usize) -> MessageResult<Option<(Vec<u8>, usize)>> {
+    let mut unescaped: Vec<u8> = Vec::with_capacity(count);
+    let mut position: usize = start;
+    while unescaped.len() < count {
+        match buffer.get(position) {
+            None => return Ok(None),
+            Some(&BEAST_ESCAPE) => match buffer.get(position + 1) {
+                None => return Ok(None),
+                Some(&BEAST_ESCAPE) => position += 2,
+                Some(_) => {
+                    return Err(message_error(format!(
+                        "Unescaped 0x1a inside Beast frame at byte {}",
+                        position
+                    )))
+                }
+            },
+            Some(_) => position += 1,
+        }
+        unescaped.push(buffer[position - 1]);
+    }
+    Ok(Some((unescaped, position)))
+}
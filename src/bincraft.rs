@@ -0,0 +1,232 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use crate::{message_error, MessageResult};
+
+/// Size of the smallest record layout, readsb has only ever grown the stride.
+const BINCRAFT_MIN_STRIDE: usize = 108;
+
+/// Names of the address types, indexed by the high nibble of byte 67 of a record.
+const BINCRAFT_ADDRESS_TYPES: [&str; 14] = [
+    "adsb_icao",
+    "adsb_icao_nt",
+    "adsr_icao",
+    "tisb_icao",
+    "adsc",
+    "mlat",
+    "other",
+    "mode_s",
+    "adsb_other",
+    "adsr_other",
+    "tisb_trackfile",
+    "tisb_other",
+    "mode_ac",
+    "unknown",
+];
+
+/// Names of the emergency states, indexed by the low nibble of byte 67 of a record.
+const BINCRAFT_EMERGENCIES: [&str; 8] = [
+    "none",
+    "general",
+    "lifeguard",
+    "minfuel",
+    "nordo",
+    "unlawful",
+    "downed",
+    "reserved",
+];
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a complete, uncompressed readsb binCraft buffer, and will not consume the source.
+/// tar1090 fetches these compressed, so they have to be decompressed first.
+///
+/// The buffer is a header record followed by one little endian aircraft record per aircraft, all of the size given in the header.
+/// Records without a valid position are kept with their position fields left empty.
+pub trait NewBinCraftMessages {
+    fn to_bincraft(&self) -> MessageResult<Vec<ADSBJsonMessage>>;
+}
+
+/// Implementing `.to_bincraft()` for the type `Vec<u8>`.
+///
+/// This does not consume the `Vec<u8>`.
+impl NewBinCraftMessages for Vec<u8> {
+    fn to_bincraft(&self) -> MessageResult<Vec<ADSBJsonMessage>> {
+        self.as_slice().to_bincraft()
+    }
+}
+
+/// Supporting `.to_bincraft()` for the type `[u8]`.
+///
+/// This does not consume the `[u8]`.
+impl NewBinCraftMessages for [u8] {
+    fn to_bincraft(&self) -> MessageResult<Vec<ADSBJsonMessage>> {
+        if self.len() < 12 {
+            return Err(message_error(format!(
+                "binCraft buffer of {} bytes is too short for a header",
+                self.len()
+            )));
+        }
+        let header: BinCraftRecord = BinCraftRecord(self);
+        let now: f64 = (header.u32(4) as f64 * 4_294_967_296.0 + header.u32(0) as f64) / 1000.0;
+        let stride: usize = header.u32(8) as usize;
+        if stride < BINCRAFT_MIN_STRIDE || !self.len().is_multiple_of(stride) {
+            return Err(message_error(format!(
+                "binCraft buffer of {} bytes does not hold records of {} bytes",
+                self.len(),
+                stride
+            )));
+        }
+        Ok(self
+            .chunks_exact(stride)
+            .skip(1)
+            .map(|record| BinCraftRecord(record).to_adsb(now))
+            .collect())
+    }
+}
+
+/// A single record of a binCraft buffer.
+struct BinCraftRecord<'a>(&'a [u8]);
+
+impl BinCraftRecord<'_> {
+    fn u8(&self, offset: usize) -> u8 {
+        self.0[offset]
+    }
+
+    fn u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.0[offset], self.0[offset + 1]])
+    }
+
+    fn i16(&self, offset: usize) -> i16 {
+        i16::from_le_bytes([self.0[offset], self.0[offset + 1]])
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes([
+            self.0[offset],
+            self.0[offset + 1],
+            self.0[offset + 2],
+            self.0[offset + 3],
+        ])
+    }
+
+    fn i32(&self, offset: usize) -> i32 {
+        self.u32(offset) as i32
+    }
+
+    /// Returns `true` if `bit` of the validity byte at `offset` is set.
+    fn valid(&self, offset: usize, bit: u8) -> bool {
+        self.u8(offset) & (1 << bit) != 0
+    }
+
+    /// Returns `value` if `bit` of the validity byte at `offset` is set.
+    fn if_valid<T>(&self, offset: usize, bit: u8, value: T) -> Option<T> {
+        match self.valid(offset, bit) {
+            true => Some(value),
+            false => None,
+        }
+    }
+
+    /// Reads a NUL padded ASCII string.
+    fn text(&self, start: usize, end: usize) -> String {
+        self.0[start..end]
+            .iter()
+            .take_while(|character| **character != 0)
+            .map(|character| *character as char)
+            .collect()
+    }
+
+    fn to_adsb(&self, now: f64) -> ADSBJsonMessage {
+        let address: i32 = self.i32(0);
+        let hex: String = match address & (1 << 24) {
+            0 => format!("{:06x}", address & 0xffffff),
+            _ => format!("~{:06x}", address & 0xffffff),
+        };
+        let alt_baro: Option<Altitude> = match (self.u8(68) & 0x0f, self.valid(73, 4)) {
+            (1, _) => Some(Altitude::Ground("ground".to_string())),
+            (_, true) => Some(Altitude::I32(self.i16(20) as i32 * 25)),
+            (_, false) => None,
+        };
+        let nav_mode_bits: u8 = self.u8(66);
+        let nav_modes: Vec<NavModes> = [
+            (0x01, NavModes::AutoPilot),
+            (0x02, NavModes::VNAV),
+            (0x04, NavModes::AltHold),
+            (0x08, NavModes::Approach),
+            (0x10, NavModes::LNAV),
+            (0x20, NavModes::TCAS),
+        ]
+        .into_iter()
+        .filter(|(bit, _)| nav_mode_bits & bit != 0)
+        .map(|(_, mode)| mode)
+        .collect();
+        let rssi: f32 = self.u8(105) as f32;
+        ADSBJsonMessage {
+            now,
+            hex,
+            adsb_type: BINCRAFT_ADDRESS_TYPES
+                .get((self.u8(67) >> 4) as usize)
+                .unwrap_or(&"unknown")
+                .to_string(),
+            flight: self.if_valid(73, 3, self.text(78, 86)),
+            aircraft_registration: self.text(92, 104),
+            aircraft_type: Some(self.text(88, 92)).filter(|text| !text.is_empty()),
+            alt_baro,
+            alt_geom: self.if_valid(73, 5, self.i16(22) as i32 * 25),
+            gs: self.if_valid(73, 7, self.i16(34) as f32 / 10.0),
+            ias: self.if_valid(74, 0, self.u16(58) as i32),
+            tas: self.if_valid(74, 1, self.u16(56) as i32),
+            mach: self.if_valid(74, 2, self.i16(36) as f32 / 1000.0),
+            wd: self.if_valid(77, 4, self.i16(48) as i32),
+            ws: self.if_valid(77, 4, self.i16(50) as i32),
+            oat: self.if_valid(77, 5, self.i16(52) as i32),
+            tat: self.if_valid(77, 5, self.i16(54) as i32),
+            track: self.if_valid(74, 3, self.i16(40) as f32 / 90.0),
+            track_rate: self.if_valid(74, 4, self.i16(42) as f32 / 100.0),
+            roll: self.if_valid(74, 5, self.i16(38) as f32 / 100.0),
+            mag_heading: self.if_valid(74, 6, self.i16(44) as f32 / 90.0),
+            baro_rate: self.if_valid(75, 0, self.i16(16) as i32 * 8),
+            geom_rate: self.if_valid(75, 1, self.i16(18) as i32 * 8),
+            squawk: self.if_valid(76, 2, format!("{:04x}", self.u16(32))),
+            emergency: self.if_valid(
+                76,
+                3,
+                BINCRAFT_EMERGENCIES[(self.u8(67) & 0x07) as usize].to_string(),
+            ),
+            category: Some(self.u8(64))
+                .filter(|category| *category != 0)
+                .map(|category| format!("{:02X}", category)),
+            nav_qnh: self.if_valid(76, 5, self.i16(28) as f32 / 10.0),
+            nav_altitude_mcp: self.if_valid(76, 6, self.u16(24) as i32 * 4),
+            nav_altitude_fms: self.if_valid(76, 7, self.u16(26) as i32 * 4),
+            nav_heading: self.if_valid(77, 1, self.i16(30) as f32 / 90.0),
+            true_heading: self.if_valid(74, 7, self.i16(46) as f32 / 90.0),
+            nav_modes: self.if_valid(77, 2, nav_modes),
+            lat: self.if_valid(73, 6, self.i32(12) as f32 / 1e6),
+            lon: self.if_valid(73, 6, self.i32(8) as f32 / 1e6),
+            nic: self.if_valid(73, 6, self.u8(65) as i32),
+            rc: self.if_valid(73, 6, self.u16(60) as i32),
+            seen_pos: self.if_valid(73, 6, self.u16(4) as f64 / 10.0),
+            seen: self.u16(6) as f64 / 10.0,
+            version: Some((self.u8(69) >> 4) as i32),
+            nic_baro: self.if_valid(75, 4, (self.u8(73) & 0x01) as i8),
+            nac_p: self.if_valid(75, 5, (self.u8(71) & 0x0f) as i8),
+            nac_v: self.if_valid(75, 6, (self.u8(71) >> 4) as i8),
+            sil: self.if_valid(75, 7, (self.u8(72) & 0x03) as i8),
+            sil_type: match self.u8(69) & 0x0f {
+                2 => SilType::PerSample,
+                3 => SilType::PerHour,
+                _ => SilType::Unknown,
+            },
+            gva: self.if_valid(76, 0, ((self.u8(72) >> 2) & 0x03) as i8),
+            sda: self.if_valid(76, 1, ((self.u8(72) >> 4) & 0x03) as i8),
+            alert: self.if_valid(77, 3, ((self.u8(73) >> 1) & 0x01) as i8),
+            spi: self.if_valid(76, 4, ((self.u8(73) >> 2) & 0x01) as i8),
+            mlat: Vec::new(),
+            tisb: Vec::new(),
+            messages: self.u16(62) as i32,
+            rssi: 10.0 * (rssi * rssi / 65025.0 + 1.125e-5).log10(),
+            dbflags: Some(self.u16(86) as i32),
+            calc_track: None,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// Characters of the 6 bit subset of IA-5 used for callsigns, indexed by their code.
+const IA5_CHARACTERS: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+/// Packs up to 8 characters of a callsign into 6 octets of 6 bit IA-5, padding with spaces.
+pub(crate) fn encode_callsign(callsign: &str) -> [u8; 6] {
+    let mut packed: u64 = 0;
+    let mut characters = callsign
+        .bytes()
+        .map(|character| character.to_ascii_uppercase());
+    for _ in 0..8 {
+        let code: u8 = match characters.next() {
+            None => 32,
+            Some(character) => IA5_CHARACTERS
+                .iter()
+                .position(|candidate| *candidate == character && character != b'#')
+                .unwrap_or(32) as u8,
+        };
+        packed = packed << 6 | code as u64;
+    }
+    let bytes: [u8; 8] = packed.to_be_bytes();
+    [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+}
+
+/// Unpacks 6 octets of 6 bit IA-5 into a callsign, keeping the trailing spaces as readsb does.
+pub(crate) fn decode_callsign(octets: &[u8]) -> String {
+    let packed: u64 = octets
+        .iter()
+        .take(6)
+        .fold(0u64, |packed, octet| packed << 8 | *octet as u64);
+    (0..8)
+        .rev()
+        .map(|index| IA5_CHARACTERS[((packed >> (index * 6)) & 0x3f) as usize] as char)
+        .collect()
+}
@@ -0,0 +1,196 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude};
+use crate::{timestamp_to_date_time, METRES_PER_FOOT, METRES_PER_SECOND_PER_KNOT};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Seconds after the event time that a TAK client should consider the event stale.
+pub const COT_STALE_SECONDS: f64 = 60.0;
+
+/// Value CoT uses for an unknown height or error.
+pub const COT_UNKNOWN: f32 = 9_999_999.0;
+
+/// Format of the timestamps in a CoT event.
+const COT_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+/// Bit of `dbflags` set for military aircraft.
+const DBFLAGS_MILITARY: i32 = 1;
+
+/// A Cursor-on-Target `<event>`, as consumed by ATAK, WinTAK and other TAK clients.
+///
+/// Heights and errors are in metres, speeds in metres per second and times in UTC.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CotEvent {
+    pub uid: String,          // unique identifier, ICAO- followed by the ICAO address
+    pub event_type: String,   // MIL-STD-2525 type, such as a-n-A-C-F
+    pub how: String,          // how the position was produced, m-g for ADS-B and m-f for MLAT
+    pub time: NaiveDateTime,  // when the event was generated
+    pub start: NaiveDateTime, // when the event becomes valid
+    pub stale: NaiveDateTime, // when the event is no longer valid
+    pub lat: f32,             // latitude
+    pub lon: f32,             // longitude
+    pub hae: f32,             // height above the WGS84 ellipsoid, metres
+    pub ce: f32,              // circular error, metres
+    pub le: f32,              // linear (vertical) error, metres
+    pub callsign: String,     // callsign shown by the client
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub course: Option<f32>, // track, degrees clockwise from north
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>, // ground speed, metres/second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remarks: Option<String>, // free text remarks
+}
+
+impl CotEvent {
+    /// Places the aircraft in an `ADSBJsonMessage` on the TAK map as a CoT event, with its height in metres taken from the geometric altitude when there is one.
+    ///
+    /// Returns `None` if the aircraft has no position, as every CoT event needs a point.
+    /// The event time is when the data was last received, derived from `now` less `seen`, and the event is stale `COT_STALE_SECONDS` later.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> Option<CotEvent> {
+        let (lat, lon) = message.position()?;
+        let time: NaiveDateTime = timestamp_to_date_time(message.now - message.seen)?;
+        let stale: NaiveDateTime =
+            timestamp_to_date_time(message.now - message.seen + COT_STALE_SECONDS)?;
+        let is_mlat: bool = message.is_mlat_position();
+        let hae: f32 = match (message.alt_geom, &message.alt_baro) {
+            (Some(altitude), _) => altitude as f32 * METRES_PER_FOOT,
+            (None, Some(Altitude::I32(altitude))) => *altitude as f32 * METRES_PER_FOOT,
+            (None, Some(Altitude::Ground(_)) | None) => COT_UNKNOWN,
+        };
+        let callsign: String = match message.flight.as_deref().map(str::trim) {
+            Some(flight) if !flight.is_empty() => flight.to_string(),
+            _ => match message.aircraft_registration.is_empty() {
+                true => message.hex.to_uppercase(),
+                false => message.aircraft_registration.clone(),
+            },
+        };
+        let mut remarks: Vec<String> = Vec::new();
+        if !message.aircraft_registration.is_empty() {
+            remarks.push(format!("Registration {}", message.aircraft_registration));
+        }
+        if let Some(aircraft_type) = &message.aircraft_type {
+            remarks.push(format!("type {}", aircraft_type));
+        }
+        if let Some(squawk) = &message.squawk {
+            remarks.push(format!("squawk {}", squawk));
+        }
+        Some(CotEvent {
+            uid: format!("ICAO-{}", message.hex.to_uppercase()),
+            event_type: cot_type(message.category.as_deref(), message.dbflags).to_string(),
+            how: match is_mlat {
+                true => "m-f".to_string(),
+                false => "m-g".to_string(),
+            },
+            time,
+            start: time,
+            stale,
+            lat,
+            lon,
+            hae,
+            ce: circular_error(message.nac_p),
+            le: linear_error(message.gva),
+            callsign,
+            course: message.track,
+            speed: message.gs.map(|gs| gs * METRES_PER_SECOND_PER_KNOT),
+            remarks: match remarks.is_empty() {
+                true => None,
+                false => Some(remarks.join(", ")),
+            },
+        })
+    }
+
+    /// Converts `CotEvent` to an XML `<event>` element, without an XML declaration.
+    pub fn to_cot_string(&self) -> String {
+        let mut detail: String = format!("<contact callsign=\"{}\"/>", escape_xml(&self.callsign));
+        if self.course.is_some() || self.speed.is_some() {
+            detail.push_str(&format!(
+                "<track course=\"{:.2}\" speed=\"{:.2}\"/>",
+                self.course.unwrap_or_default(),
+                self.speed.unwrap_or_default()
+            ));
+        }
+        if let Some(remarks) = &self.remarks {
+            detail.push_str(&format!("<remarks>{}</remarks>", escape_xml(remarks)));
+        }
+        format!(
+            "<event version=\"2.0\" uid=\"{}\" type=\"{}\" how=\"{}\" time=\"{}\" start=\"{}\" stale=\"{}\">\
+<point lat=\"{:.6}\" lon=\"{:.6}\" hae=\"{:.1}\" ce=\"{:.1}\" le=\"{:.1}\"/>\
+<detail>{}</detail></event>",
+            escape_xml(&self.uid),
+            escape_xml(&self.event_type),
+            escape_xml(&self.how),
+            self.time.format(COT_TIME_FORMAT),
+            self.start.format(COT_TIME_FORMAT),
+            self.stale.format(COT_TIME_FORMAT),
+            self.lat,
+            self.lon,
+            self.hae,
+            self.ce,
+            self.le,
+            detail,
+        )
+    }
+
+    /// Converts `CotEvent` to an XML `<event>` element and appends a `\n` to the end.
+    pub fn to_cot_string_newline(&self) -> String {
+        format!("{}\n", self.to_cot_string())
+    }
+
+    /// Converts `CotEvent` to an XML `<event>` element encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_cot_string().into_bytes()
+    }
+}
+
+/// Returns the MIL-STD-2525 type for an aircraft from its emitter category and the military bit of `dbflags`.
+fn cot_type(category: Option<&str>, dbflags: Option<i32>) -> &'static str {
+    let military: bool = dbflags.unwrap_or_default() & DBFLAGS_MILITARY != 0;
+    match (category, military) {
+        (Some("A7"), false) => "a-n-A-C-H",
+        (Some("A7"), true) => "a-n-A-M-H",
+        (Some("B2"), false) => "a-n-A-C-L",
+        (Some("B2"), true) => "a-n-A-M-L",
+        (Some("B6"), true) => "a-n-A-M-F-Q",
+        (Some("C1") | Some("C2"), _) => "a-n-G-E-V",
+        (Some("C3") | Some("C4") | Some("C5"), _) => "a-n-G",
+        (_, false) => "a-n-A-C-F",
+        (_, true) => "a-n-A-M-F",
+    }
+}
+
+/// Converts a Navigation Accuracy Category for Position to the bound of its estimated position uncertainty, metres.
+fn circular_error(nac_p: Option<i8>) -> f32 {
+    match nac_p {
+        Some(11) => 3.0,
+        Some(10) => 10.0,
+        Some(9) => 30.0,
+        Some(8) => 92.6,
+        Some(7) => 185.2,
+        Some(6) => 555.6,
+        Some(5) => 926.0,
+        Some(4) => 1852.0,
+        Some(3) => 3704.0,
+        Some(2) => 7408.0,
+        Some(1) => 18520.0,
+        _ => COT_UNKNOWN,
+    }
+}
+
+/// Converts a Geometric Vertical Accuracy to the bound of its vertical uncertainty, metres.
+fn linear_error(gva: Option<i8>) -> f32 {
+    match gva {
+        Some(1) => 150.0,
+        Some(2) | Some(3) => 45.0,
+        _ => COT_UNKNOWN,
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
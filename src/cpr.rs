@@ -0,0 +1,297 @@
+use crate::adsb_json::ADSBJsonMessage;
+use crate::modes::extended_squitter::ExtendedSquitter;
+use crate::modes::ModeSMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Seconds an airborne even and odd frame can be apart and still be decoded as a pair.
+pub const CPR_AIRBORNE_PAIR_SECONDS: f64 = 10.0;
+
+/// Seconds a surface even and odd frame can be apart and still be decoded as a pair.
+pub const CPR_SURFACE_PAIR_SECONDS: f64 = 25.0;
+
+/// Seconds a decoded position is kept as the reference for local decoding of later frames.
+pub const CPR_REFERENCE_SECONDS: f64 = 600.0;
+
+/// Number of latitude zones between the equator and a pole.
+const NZ: f64 = 15.0;
+
+/// Count of the 17 bit CPR encoded latitude and longitude.
+const CPR_SCALE: f64 = 131_072.0;
+
+/// A CPR encoded position from one airborne or surface position frame.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CprFrame {
+    pub lat_cpr: u32,   // 17 bit CPR encoded latitude
+    pub lon_cpr: u32,   // 17 bit CPR encoded longitude
+    pub odd: bool,      // odd rather than even format
+    pub surface: bool,  // surface rather than airborne format
+    pub timestamp: f64, // Unix timestamp the frame was received
+}
+
+impl CprFrame {
+    /// Takes the CPR encoded position from an airborne or surface position frame.
+    pub fn from_modes(message: &ModeSMessage, timestamp: f64) -> Option<CprFrame> {
+        match &message.extended_squitter {
+            Some(ExtendedSquitter::AirbornePosition(position)) => Some(CprFrame {
+                lat_cpr: position.lat_cpr,
+                lon_cpr: position.lon_cpr,
+                odd: position.odd,
+                surface: false,
+                timestamp,
+            }),
+            Some(ExtendedSquitter::SurfacePosition(position)) => Some(CprFrame {
+                lat_cpr: position.lat_cpr,
+                lon_cpr: position.lon_cpr,
+                odd: position.odd,
+                surface: true,
+                timestamp,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the degrees of latitude or longitude covered by the whole CPR encoding, 360 airborne or 90 on the surface.
+    fn span(&self) -> f64 {
+        match self.surface {
+            true => 90.0,
+            false => 360.0,
+        }
+    }
+}
+
+/// Returns the number of longitude zones at a latitude, from 59 at the equator to 1 at the poles.
+pub fn cpr_nl(lat: f64) -> u32 {
+    let lat: f64 = lat.abs();
+    if lat == 0.0 {
+        return 59;
+    }
+    if lat > 87.0 {
+        return 1;
+    }
+    if lat == 87.0 {
+        return 2;
+    }
+    let a: f64 = 1.0 - (std::f64::consts::PI / (2.0 * NZ)).cos();
+    let b: f64 = lat.to_radians().cos().powi(2);
+    (2.0 * std::f64::consts::PI / (1.0 - a / b).acos()).floor() as u32
+}
+
+/// Decodes the position of a pair of even and odd frames, at the time of the most recent one.
+///
+/// Surface frames only cover a quarter of the globe, so they need a `reference`, such as the receiver, to choose the quadrant.
+/// Returns `None` if the frames straddle a longitude zone boundary, or a surface pair has no reference.
+pub fn decode_global(
+    even: &CprFrame,
+    odd: &CprFrame,
+    reference: Option<(f64, f64)>,
+) -> Option<(f64, f64)> {
+    if even.odd || !odd.odd || even.surface != odd.surface {
+        return None;
+    }
+    let surface: bool = even.surface;
+    let span: f64 = even.span();
+    let reference: Option<(f64, f64)> = match (surface, reference) {
+        (true, None) => return None,
+        (_, reference) => reference,
+    };
+    let lat_even_cpr: f64 = even.lat_cpr as f64 / CPR_SCALE;
+    let lat_odd_cpr: f64 = odd.lat_cpr as f64 / CPR_SCALE;
+    let j: f64 = (59.0 * lat_even_cpr - 60.0 * lat_odd_cpr + 0.5).floor();
+    let mut lat_even: f64 = span / 60.0 * (j.rem_euclid(60.0) + lat_even_cpr);
+    let mut lat_odd: f64 = span / 59.0 * (j.rem_euclid(59.0) + lat_odd_cpr);
+    match reference {
+        // Surface latitudes are either in the northern hemisphere or 90 degrees south of it.
+        Some((reference_lat, _)) if surface => {
+            if (lat_even - 90.0 - reference_lat).abs() < (lat_even - reference_lat).abs() {
+                lat_even -= 90.0;
+            }
+            if (lat_odd - 90.0 - reference_lat).abs() < (lat_odd - reference_lat).abs() {
+                lat_odd -= 90.0;
+            }
+        }
+        _ => {
+            if lat_even >= 270.0 {
+                lat_even -= 360.0;
+            }
+            if lat_odd >= 270.0 {
+                lat_odd -= 360.0;
+            }
+        }
+    }
+    if !(-90.0..=90.0).contains(&lat_even) || !(-90.0..=90.0).contains(&lat_odd) {
+        return None;
+    }
+    let nl: u32 = cpr_nl(lat_even);
+    if nl != cpr_nl(lat_odd) {
+        debug!(
+            "CPR pair straddles a longitude zone boundary at {} and {}",
+            lat_even, lat_odd
+        );
+        return None;
+    }
+    let (lat, latest): (f64, &CprFrame) = match odd.timestamp > even.timestamp {
+        true => (lat_odd, odd),
+        false => (lat_even, even),
+    };
+    let ni: f64 = (nl as f64 - latest.odd as u8 as f64).max(1.0);
+    let m: f64 = ((even.lon_cpr as f64 * (nl as f64 - 1.0) - odd.lon_cpr as f64 * nl as f64)
+        / CPR_SCALE
+        + 0.5)
+        .floor();
+    let mut lon: f64 = span / ni * (m.rem_euclid(ni) + latest.lon_cpr as f64 / CPR_SCALE);
+    match reference {
+        // Surface longitudes repeat every 90 degrees, so take the repeat nearest the reference.
+        Some((_, reference_lon)) if surface => {
+            lon = (0..4)
+                .map(|quadrant| normalise_longitude(lon + quadrant as f64 * 90.0))
+                .min_by(|a, b| {
+                    longitude_difference(*a, reference_lon)
+                        .total_cmp(&longitude_difference(*b, reference_lon))
+                })
+                .unwrap_or(lon);
+        }
+        _ => lon = normalise_longitude(lon),
+    }
+    Some((lat, lon))
+}
+
+/// Decodes the position of a single frame relative to a `reference` position, such as the receiver or the aircraft's last position.
+///
+/// The result is only unambiguous if the aircraft is within half a latitude zone of the reference,
+/// about 180 nautical miles airborne and 45 on the surface.
+pub fn decode_local(frame: &CprFrame, reference: (f64, f64)) -> Option<(f64, f64)> {
+    let (reference_lat, reference_lon) = reference;
+    let span: f64 = frame.span();
+    let lat_cpr: f64 = frame.lat_cpr as f64 / CPR_SCALE;
+    let lon_cpr: f64 = frame.lon_cpr as f64 / CPR_SCALE;
+    let d_lat: f64 = match frame.odd {
+        true => span / 59.0,
+        false => span / 60.0,
+    };
+    let j: f64 = (reference_lat / d_lat).floor()
+        + (0.5 + reference_lat.rem_euclid(d_lat) / d_lat - lat_cpr).floor();
+    let lat: f64 = d_lat * (j + lat_cpr);
+    if !(-90.0..=90.0).contains(&lat) {
+        return None;
+    }
+    let ni: f64 = (cpr_nl(lat) as f64 - frame.odd as u8 as f64).max(1.0);
+    let d_lon: f64 = span / ni;
+    let m: f64 = (reference_lon / d_lon).floor()
+        + (0.5 + reference_lon.rem_euclid(d_lon) / d_lon - lon_cpr).floor();
+    Some((lat, normalise_longitude(d_lon * (m + lon_cpr))))
+}
+
+/// Decodes CPR positions for many aircraft, keeping each aircraft's latest even and odd frames and last position.
+///
+/// Positions are decoded globally when a fresh even and odd pair is held, and otherwise locally relative to the aircraft's last position.
+/// The receiver location, when known, resolves the quadrant of surface positions and is the local reference for surface frames of new aircraft.
+#[derive(Debug, Clone, Default)]
+pub struct CprDecoder {
+    pub receiver: Option<(f64, f64)>, // receiver latitude and longitude
+    aircraft: HashMap<u32, CprAircraft>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CprAircraft {
+    even: Option<CprFrame>,
+    odd: Option<CprFrame>,
+    position: Option<(f64, f64, f64)>, // latitude, longitude and the Unix timestamp it was decoded
+}
+
+impl CprDecoder {
+    pub fn new(receiver: Option<(f64, f64)>) -> CprDecoder {
+        CprDecoder {
+            receiver,
+            aircraft: HashMap::new(),
+        }
+    }
+
+    /// Adds a frame for the aircraft with `address` and returns its decoded position, if one can be found yet.
+    pub fn decode(&mut self, address: u32, frame: CprFrame) -> Option<(f64, f64)> {
+        let receiver: Option<(f64, f64)> = self.receiver;
+        let aircraft: &mut CprAircraft = self.aircraft.entry(address).or_default();
+        // Frames of the other format, airborne or surface, cannot be paired with this one.
+        if aircraft
+            .even
+            .is_some_and(|even| even.surface != frame.surface)
+        {
+            aircraft.even = None;
+        }
+        if aircraft.odd.is_some_and(|odd| odd.surface != frame.surface) {
+            aircraft.odd = None;
+        }
+        match frame.odd {
+            true => aircraft.odd = Some(frame),
+            false => aircraft.even = Some(frame),
+        }
+        let last_position: Option<(f64, f64)> = match aircraft.position {
+            Some((lat, lon, timestamp)) if frame.timestamp - timestamp <= CPR_REFERENCE_SECONDS => {
+                Some((lat, lon))
+            }
+            _ => None,
+        };
+        let pair_seconds: f64 = match frame.surface {
+            true => CPR_SURFACE_PAIR_SECONDS,
+            false => CPR_AIRBORNE_PAIR_SECONDS,
+        };
+        let global: Option<(f64, f64)> = match (aircraft.even, aircraft.odd) {
+            (Some(even), Some(odd)) if (even.timestamp - odd.timestamp).abs() <= pair_seconds => {
+                decode_global(&even, &odd, last_position.or(receiver))
+            }
+            _ => None,
+        };
+        // Surface frames are only heard close to the receiver, well inside the range where a local decode is unambiguous.
+        let local_reference: Option<(f64, f64)> = match frame.surface {
+            true => last_position.or(receiver),
+            false => last_position,
+        };
+        let position: Option<(f64, f64)> = global
+            .or_else(|| local_reference.and_then(|reference| decode_local(&frame, reference)));
+        if let Some((lat, lon)) = position {
+            aircraft.position = Some((lat, lon, frame.timestamp));
+        }
+        trace!("CPR position of {:06x}: {:?}", address, position);
+        position
+    }
+
+    /// Adds the frame of an airborne or surface position message and returns its decoded position, if one can be found yet.
+    pub fn decode_message(&mut self, message: &ModeSMessage, now: f64) -> Option<(f64, f64)> {
+        let frame: CprFrame = CprFrame::from_modes(message, now)?;
+        self.decode(message.address, frame)
+    }
+
+    /// Converts a `ModeSMessage` to an `ADSBJsonMessage`, filling `lat`, `lon` and `seen_pos` when its position can be decoded.
+    pub fn to_adsb_json(&mut self, message: &ModeSMessage, now: f64) -> ADSBJsonMessage {
+        let mut adsb_json: ADSBJsonMessage = message.to_adsb_json(now);
+        if let Some((lat, lon)) = self.decode_message(message, now) {
+            adsb_json.lat = Some(lat as f32);
+            adsb_json.lon = Some(lon as f32);
+            adsb_json.seen_pos = Some(0.0);
+        }
+        adsb_json
+    }
+
+    /// Forgets the frames and positions of aircraft not heard from in the last `CPR_REFERENCE_SECONDS` before `now`.
+    pub fn expire(&mut self, now: f64) {
+        self.aircraft.retain(|_, aircraft| {
+            [
+                aircraft.even.map(|even| even.timestamp),
+                aircraft.odd.map(|odd| odd.timestamp),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|timestamp| now - timestamp <= CPR_REFERENCE_SECONDS)
+        });
+    }
+}
+
+/// Wraps a longitude into -180 to 180 degrees.
+fn normalise_longitude(lon: f64) -> f64 {
+    (lon + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Returns the smaller angle between two longitudes, in degrees.
+fn longitude_difference(a: f64, b: f64) -> f64 {
+    normalise_longitude(a - b).abs()
+}
@@ -0,0 +1,443 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use crate::{message_error, MessageResult};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single faup1090 line of tab separated keys and values, and will not consume the source.
+/// Keys this library does not know are skipped.
+pub trait NewFaupMessage {
+    fn to_faup(&self) -> MessageResult<FaupMessage>;
+}
+
+/// Implementing `.to_faup()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewFaupMessage for String {
+    fn to_faup(&self) -> MessageResult<FaupMessage> {
+        self.as_str().to_faup()
+    }
+}
+
+/// Supporting `.to_faup()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewFaupMessage for str {
+    fn to_faup(&self) -> MessageResult<FaupMessage> {
+        let fields: Vec<&str> = self.trim_end_matches(['\r', '\n']).split('\t').collect();
+        if !fields.len().is_multiple_of(2) {
+            return Err(message_error(format!(
+                "faup1090 line has a key without a value: {}",
+                self
+            )));
+        }
+        let mut message: FaupMessage = FaupMessage::default();
+        let mut has_clock: bool = false;
+        for pair in fields.chunks(2) {
+            let (key, value) = (pair[0], unbrace(pair[1]));
+            match key {
+                "clock" => {
+                    message.clock = parse_value(value, key)?;
+                    has_clock = true;
+                }
+                "hexid" | "otherid" => {
+                    message.hexid = value.to_uppercase();
+                    message.non_icao = key == "otherid";
+                }
+                "addrtype" => message.addrtype = Some(value.to_string()),
+                "adsb_version" => message.adsb_version = Some(parse_value(value, key)?),
+                "category" => message.category = Some(value.to_string()),
+                "ident" => message.ident = Some(value.to_string()),
+                "squawk" => message.squawk = Some(value.to_string()),
+                "alt" => message.alt = Some(parse_value(value, key)?),
+                "alt_geom" => message.alt_geom = Some(parse_value(value, key)?),
+                "airGround" => message.air_ground = Some(value.to_string()),
+                "position" => {
+                    let parts: Vec<&str> = value.split_whitespace().collect();
+                    if parts.len() < 2 {
+                        return Err(message_error(format!(
+                            "Invalid faup1090 position: {}",
+                            value
+                        )));
+                    }
+                    message.position = Some(FaupPosition {
+                        lat: parse_value(parts[0], "latitude")?,
+                        lon: parse_value(parts[1], "longitude")?,
+                        nic: parse_optional(parts.get(2), "nic")?,
+                        rc: parse_optional(parts.get(3), "rc")?,
+                    });
+                }
+                "speed" => message.speed = Some(parse_value(value, key)?),
+                "ias" => message.ias = Some(parse_value(value, key)?),
+                "tas" => message.tas = Some(parse_value(value, key)?),
+                "mach" => message.mach = Some(parse_value(value, key)?),
+                "heading" => message.heading = Some(parse_value(value, key)?),
+                "heading_magnetic" => message.heading_magnetic = Some(parse_value(value, key)?),
+                "heading_true" => message.heading_true = Some(parse_value(value, key)?),
+                "vrate" => message.vrate = Some(parse_value(value, key)?),
+                "vrate_geom" => message.vrate_geom = Some(parse_value(value, key)?),
+                "nav_alt_mcp" => message.nav_alt_mcp = Some(parse_value(value, key)?),
+                "nav_alt_fms" => message.nav_alt_fms = Some(parse_value(value, key)?),
+                "nav_heading" => message.nav_heading = Some(parse_value(value, key)?),
+                "nav_qnh" => message.nav_qnh = Some(parse_value(value, key)?),
+                "nav_modes" => {
+                    let mut nav_modes: Vec<NavModes> = Vec::new();
+                    for mode in value.split_whitespace() {
+                        nav_modes.push(serde_json::from_value(serde_json::Value::from(mode))?);
+                    }
+                    message.nav_modes = Some(nav_modes);
+                }
+                "emergency" => message.emergency = Some(value.to_string()),
+                "nac_p" => message.nac_p = Some(parse_value(value, key)?),
+                "nac_v" => message.nac_v = Some(parse_value(value, key)?),
+                "nic_baro" => message.nic_baro = Some(parse_value(value, key)?),
+                "sil" => message.sil = Some(parse_value(value, key)?),
+                "sil_type" => message.sil_type = Some(serde_json::from_value(value.into())?),
+                "spi" => message.spi = Some(parse_flag(value, key)?),
+                "alert" => message.alert = Some(parse_flag(value, key)?),
+                _ => trace!("Skipping faup1090 key {}", key),
+            }
+        }
+        if !has_clock || message.hexid.is_empty() {
+            return Err(message_error(format!(
+                "faup1090 line is missing clock or hexid: {}",
+                self
+            )));
+        }
+        Ok(message)
+    }
+}
+
+/// A faup1090 line, the tab separated `key\tvalue` report piaware reads from faup1090 for each aircraft.
+///
+/// Values that can hold spaces, such as `ident`, `position` and `nav_modes`, are wrapped in braces as Tcl lists.
+/// Altitudes are in feet, speeds in knots and vertical rates in feet per minute.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FaupMessage {
+    pub clock: u64,     // Unix timestamp the data was last received, seconds
+    pub hexid: String,  // ICAO address, upper case
+    pub non_icao: bool, // the address is not an ICAO address, written as otherid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addrtype: Option<String>, // address type, such as adsb_icao
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adsb_version: Option<i32>, // ADS-B version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>, // emitter category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ident: Option<String>, // callsign, without padding
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub squawk: Option<String>, // squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<i32>, // barometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt_geom: Option<i32>, // geometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub air_ground: Option<String>, // A+ airborne, G+ on the ground, read by the first character
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<FaupPosition>, // position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>, // ground speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ias: Option<i32>, // indicated air speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tas: Option<i32>, // true air speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mach: Option<f32>, // mach number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<f32>, // track over the ground
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_magnetic: Option<f32>, // heading, degrees clockwise from magnetic north
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_true: Option<f32>, // heading, degrees clockwise from true north
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrate: Option<i32>, // barometric vertical rate, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vrate_geom: Option<i32>, // geometric vertical rate, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_alt_mcp: Option<i32>, // MCP/FCU selected altitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_alt_fms: Option<i32>, // FMS selected altitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_heading: Option<f32>, // selected heading
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_qnh: Option<f32>, // altimeter setting, hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_modes: Option<Vec<NavModes>>, // engaged automation modes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<String>, // emergency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nac_p: Option<i8>, // Navigation Accuracy Category for Position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nac_v: Option<i8>, // Navigation Accuracy Category for Velocity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_baro: Option<i8>, // Navigation Integrity Category for Barometric Altitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil: Option<i8>, // Source Integrity Level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil_type: Option<SilType>, // Source Integrity Level for Type of Aircraft
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spi: Option<bool>, // special position identification (ident)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<bool>, // squawk has changed
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct FaupPosition {
+    pub lat: f32, // latitude
+    pub lon: f32, // longitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic: Option<i32>, // Navigation Integrity Category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rc: Option<i32>, // Radius of Containment, meter
+}
+
+impl FaupMessage {
+    /// Writes an `ADSBJsonMessage` as the faup1090 report piaware reads.
+    /// faup1090 has no keys for the registration, the signal level or the message count, so those are dropped.
+    ///
+    /// The clock is when the data was last received, derived from `now` less `seen`.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> FaupMessage {
+        let (alt, air_ground) = match &message.alt_baro {
+            Some(Altitude::I32(altitude)) => (Some(*altitude), Some("A+")),
+            Some(Altitude::Ground(_)) => (None, Some("G+")),
+            None => (None, None),
+        };
+        FaupMessage {
+            clock: (message.now - message.seen).max(0.0) as u64,
+            hexid: message.hex.trim_start_matches('~').to_uppercase(),
+            non_icao: message.hex.starts_with('~'),
+            addrtype: Some(message.adsb_type.clone()).filter(|addrtype| !addrtype.is_empty()),
+            adsb_version: message.version,
+            category: message.category.clone(),
+            ident: message
+                .flight
+                .as_deref()
+                .map(str::trim)
+                .filter(|flight| !flight.is_empty())
+                .map(str::to_string),
+            squawk: message.squawk.clone(),
+            alt,
+            alt_geom: message.alt_geom,
+            air_ground: air_ground.map(str::to_string),
+            position: message.position().map(|(lat, lon)| FaupPosition {
+                lat,
+                lon,
+                nic: message.nic,
+                rc: message.rc,
+            }),
+            speed: message.gs,
+            ias: message.ias,
+            tas: message.tas,
+            mach: message.mach,
+            heading: message.track,
+            heading_magnetic: message.mag_heading,
+            heading_true: message.true_heading,
+            vrate: message.baro_rate,
+            vrate_geom: message.geom_rate,
+            nav_alt_mcp: message.nav_altitude_mcp,
+            nav_alt_fms: message.nav_altitude_fms,
+            nav_heading: message.nav_heading,
+            nav_qnh: message.nav_qnh,
+            nav_modes: message.nav_modes.clone(),
+            emergency: message.emergency.clone(),
+            nac_p: message.nac_p,
+            nac_v: message.nac_v,
+            nic_baro: message.nic_baro,
+            sil: message.sil,
+            sil_type: Some(message.sil_type).filter(|sil_type| *sil_type != SilType::Unknown),
+            spi: message.spi.map(|spi| spi != 0),
+            alert: message.alert.map(|alert| alert != 0),
+        }
+    }
+
+    /// Converts `FaupMessage` to an `ADSBJsonMessage`, with `now` and the data ages taken from the clock.
+    ///
+    /// Reports without a position leave the position fields empty.
+    /// Only the first character of `airGround` decides whether the aircraft is on the ground.
+    pub fn to_adsb_json(&self) -> MessageResult<ADSBJsonMessage> {
+        let on_ground: bool = self
+            .air_ground
+            .as_deref()
+            .is_some_and(|air_ground| air_ground.starts_with('G'));
+        let alt_baro: Option<Altitude> = match (on_ground, self.alt) {
+            (true, _) => Some(Altitude::Ground("ground".to_string())),
+            (_, altitude) => altitude.map(Altitude::I32),
+        };
+        Ok(ADSBJsonMessage {
+            now: self.clock as f64,
+            hex: match self.non_icao {
+                true => format!("~{}", self.hexid.to_lowercase()),
+                false => self.hexid.to_lowercase(),
+            },
+            adsb_type: self
+                .addrtype
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            flight: self.ident.clone(),
+            alt_baro,
+            alt_geom: self.alt_geom,
+            gs: self.speed,
+            ias: self.ias,
+            tas: self.tas,
+            mach: self.mach,
+            track: self.heading,
+            mag_heading: self.heading_magnetic,
+            true_heading: self.heading_true,
+            baro_rate: self.vrate,
+            geom_rate: self.vrate_geom,
+            squawk: self.squawk.clone(),
+            emergency: self.emergency.clone(),
+            category: self.category.clone(),
+            nav_qnh: self.nav_qnh,
+            nav_altitude_mcp: self.nav_alt_mcp,
+            nav_altitude_fms: self.nav_alt_fms,
+            nav_heading: self.nav_heading,
+            nav_modes: self.nav_modes.clone(),
+            lat: self.position.map(|position| position.lat),
+            lon: self.position.map(|position| position.lon),
+            nic: self.position.and_then(|position| position.nic),
+            rc: self.position.and_then(|position| position.rc),
+            seen_pos: self.position.map(|_| 0.0),
+            seen: 0.0,
+            version: self.adsb_version,
+            nic_baro: self.nic_baro,
+            nac_p: self.nac_p,
+            nac_v: self.nac_v,
+            sil: self.sil,
+            sil_type: self.sil_type.unwrap_or_default(),
+            alert: self.alert.map(|alert| alert as i8),
+            spi: self.spi.map(|spi| spi as i8),
+            messages: 1,
+            ..Default::default()
+        })
+    }
+
+    /// Converts `FaupMessage` to a faup1090 line, writing only the keys that have a value.
+    pub fn to_faup_string(&self) -> String {
+        let mut fields: Vec<(&str, String)> = vec![("clock", self.clock.to_string())];
+        match self.non_icao {
+            true => fields.push(("otherid", self.hexid.clone())),
+            false => fields.push(("hexid", self.hexid.clone())),
+        }
+        push_field(&mut fields, "addrtype", &self.addrtype);
+        push_field(&mut fields, "adsb_version", &self.adsb_version);
+        push_field(&mut fields, "category", &self.category);
+        push_field(
+            &mut fields,
+            "ident",
+            &self.ident.as_ref().map(|ident| format!("{{{}}}", ident)),
+        );
+        push_field(&mut fields, "squawk", &self.squawk);
+        push_field(&mut fields, "alt", &self.alt);
+        push_field(&mut fields, "alt_geom", &self.alt_geom);
+        push_field(&mut fields, "airGround", &self.air_ground);
+        push_field(
+            &mut fields,
+            "position",
+            &self.position.map(|position| {
+                format!(
+                    "{{{} {} {} {}}}",
+                    position.lat,
+                    position.lon,
+                    position.nic.unwrap_or_default(),
+                    position.rc.unwrap_or_default()
+                )
+            }),
+        );
+        push_field(&mut fields, "speed", &self.speed);
+        push_field(&mut fields, "ias", &self.ias);
+        push_field(&mut fields, "tas", &self.tas);
+        push_field(&mut fields, "mach", &self.mach);
+        push_field(&mut fields, "heading", &self.heading);
+        push_field(&mut fields, "heading_magnetic", &self.heading_magnetic);
+        push_field(&mut fields, "heading_true", &self.heading_true);
+        push_field(&mut fields, "vrate", &self.vrate);
+        push_field(&mut fields, "vrate_geom", &self.vrate_geom);
+        push_field(&mut fields, "nav_alt_mcp", &self.nav_alt_mcp);
+        push_field(&mut fields, "nav_alt_fms", &self.nav_alt_fms);
+        push_field(&mut fields, "nav_heading", &self.nav_heading);
+        push_field(&mut fields, "nav_qnh", &self.nav_qnh);
+        push_field(
+            &mut fields,
+            "nav_modes",
+            &self.nav_modes.as_ref().map(|nav_modes| {
+                let names: Vec<String> = nav_modes
+                    .iter()
+                    .filter_map(|mode| serde_json::to_value(mode).ok())
+                    .filter_map(|mode| mode.as_str().map(str::to_string))
+                    .collect();
+                format!("{{{}}}", names.join(" "))
+            }),
+        );
+        push_field(&mut fields, "emergency", &self.emergency);
+        push_field(&mut fields, "nac_p", &self.nac_p);
+        push_field(&mut fields, "nac_v", &self.nac_v);
+        push_field(&mut fields, "nic_baro", &self.nic_baro);
+        push_field(&mut fields, "sil", &self.sil);
+        push_field(
+            &mut fields,
+            "sil_type",
+            &self
+                .sil_type
+                .and_then(|sil_type| serde_json::to_value(sil_type).ok())
+                .and_then(|sil_type| sil_type.as_str().map(str::to_string)),
+        );
+        push_field(&mut fields, "spi", &self.spi.map(|spi| spi as u8));
+        push_field(&mut fields, "alert", &self.alert.map(|alert| alert as u8));
+        fields
+            .iter()
+            .map(|(key, value)| format!("{}\t{}", key, value))
+            .collect::<Vec<String>>()
+            .join("\t")
+    }
+
+    /// Converts `FaupMessage` to a faup1090 line and appends a `\n` to the end.
+    pub fn to_faup_string_newline(&self) -> String {
+        format!("{}\n", self.to_faup_string())
+    }
+}
+
+fn push_field<T: Display>(fields: &mut Vec<(&str, String)>, key: &'static str, value: &Option<T>) {
+    if let Some(value) = value {
+        fields.push((key, value.to_string()));
+    }
+}
+
+/// Removes the braces Tcl puts around list values.
+fn unbrace(value: &str) -> &str {
+    value
+        .strip_prefix('{')
+        .and_then(|value| value.strip_suffix('}'))
+        .unwrap_or(value)
+}
+
+fn parse_value<T: FromStr>(value: &str, name: &str) -> MessageResult<T> {
+    match value.parse::<T>() {
+        Err(_) => Err(message_error(format!(
+            "Invalid faup1090 {}: {}",
+            name, value
+        ))),
+        Ok(parsed) => Ok(parsed),
+    }
+}
+
+fn parse_optional<T: FromStr>(value: Option<&&str>, name: &str) -> MessageResult<Option<T>> {
+    match value {
+        None => Ok(None),
+        Some(value) => parse_value(value, name).map(Some),
+    }
+}
+
+/// faup1090 writes `1` for a set flag and `0` for a clear one.
+fn parse_flag(value: &str, name: &str) -> MessageResult<bool> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(message_error(format!(
+            "Invalid faup1090 {} flag: {}",
+            name, value
+        ))),
+    }
+}
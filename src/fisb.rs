@@ -0,0 +1,370 @@
+use crate::uat_json::{UatMetadata, UatPosition};
+use crate::uat_raw::{decode_lat_lon, parse_metadata, raw_lat_lon};
+use crate::{decode_hex, message_error, MessageResult};
+use serde::{Deserialize, Serialize};
+
+/// Length of a UAT uplink payload, without its Reed-Solomon parity.
+pub const UAT_UPLINK_LENGTH: usize = 432;
+/// Length of the UAT-specific header at the start of an uplink payload.
+pub const UAT_UPLINK_HEADER_LENGTH: usize = 8;
+
+/// Product ID of generic textual data, such as METARs and TAFs, encoded in DLAC.
+pub const FISB_PRODUCT_TEXT: u16 = 413;
+/// Product ID of regional NEXRAD composite reflectivity.
+pub const FISB_PRODUCT_NEXRAD_REGIONAL: u16 = 63;
+/// Product ID of CONUS NEXRAD composite reflectivity.
+pub const FISB_PRODUCT_NEXRAD_CONUS: u16 = 64;
+
+/// Number of NEXRAD blocks in a row below 60 degrees of latitude.
+const NEXRAD_BLOCKS_PER_ROW: u32 = 450;
+/// First NEXRAD block number above 60 degrees of latitude, where blocks are twice as wide.
+const NEXRAD_POLAR_BLOCK_START: u32 = 405_000;
+
+/// Characters of the 6 bit DLAC alphabet, where `\x03` ends the text, `\t` is followed by a count of spaces and `\x1e` separates records.
+const DLAC_ALPHABET: &[u8; 64] =
+    b"\x03ABCDEFGHIJKLMNOPQRSTUVWXYZ\x1a\t\x1e\n| !\"#$%&'()*+,-./0123456789:;<=>?";
+const DLAC_TAB: u8 = 28;
+const DLAC_RECORD_SEPARATOR: char = '\x1e';
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single raw dump978-fa uplink line, `+<payload>;rs=<errors>;rssi=<rssi>;t=<timestamp>;`, and will not consume the source.
+pub trait NewUatUplinkMessage {
+    fn to_fisb(&self) -> MessageResult<UatUplinkMessage>;
+}
+
+/// Implementing `.to_fisb()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewUatUplinkMessage for String {
+    fn to_fisb(&self) -> MessageResult<UatUplinkMessage> {
+        self.as_str().to_fisb()
+    }
+}
+
+/// Supporting `.to_fisb()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewUatUplinkMessage for str {
+    fn to_fisb(&self) -> MessageResult<UatUplinkMessage> {
+        let line: &str = self.trim();
+        let mut fields = match line.strip_prefix('+') {
+            None => {
+                return Err(message_error(format!(
+                    "Raw UAT uplink line must start with '+': {}",
+                    line
+                )))
+            }
+            Some(body) => body.split(';'),
+        };
+        let payload: Vec<u8> = decode_hex(fields.next().unwrap_or_default())?;
+        let mut message: UatUplinkMessage = UatUplinkMessage::from_payload(&payload)?;
+        message.metadata = parse_metadata(fields);
+        Ok(message)
+    }
+}
+
+/// A UAT uplink message sent by a ground station, carrying FIS-B products in its information frames.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatUplinkMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<UatMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<UatPosition>, // position of the ground station
+    pub utc_coupled: bool,
+    pub slot_id: u8,      // transmission slot of the ground station
+    pub tisb_site_id: u8, // TIS-B site of the ground station
+    pub info_frames: Vec<FisbInfoFrame>,
+}
+
+/// An information frame of an uplink message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum FisbInfoFrame {
+    Apdu(FisbApdu),
+    Other { frame_type: u8, data: Vec<u8> },
+}
+
+/// A FIS-B application protocol data unit, which holds a single product.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FisbApdu {
+    pub product_id: u16,
+    pub a_flag: bool,         // application method flag
+    pub g_flag: bool,         // geographic location flag
+    pub p_flag: bool,         // provider specific flag
+    pub segmented: bool,      // the product is split across several APDUs
+    pub time: FisbTime,       // product time, UTC
+    pub product: FisbProduct, // decoded product
+}
+
+/// The time of a product, where the date and seconds are only sent for some time formats.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FisbTime {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub month: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<u8>,
+    pub hours: u8,
+    pub minutes: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seconds: Option<u8>,
+}
+
+/// The decoded payload of an APDU.
+///
+/// Products other than DLAC text and NEXRAD, and segmented products, are kept as the raw payload.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum FisbProduct {
+    Text(Vec<FisbTextRecord>),
+    Nexrad(NexradBlock),
+    Unsupported(Vec<u8>),
+}
+
+/// A single record of a text product, such as a METAR or TAF.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FisbTextRecord {
+    pub record_type: String, // first word of the record, such as METAR, TAF or WINDS
+    pub location: String,    // second word of the record, usually the station
+    pub text: String,        // whole record
+}
+
+/// A NEXRAD global block, covering 4 arc minutes of latitude by 48 arc minutes of longitude, or 96 above 60 degrees.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NexradBlock {
+    pub block_number: u32,
+    pub scale_factor: u8, // 0 for 1, 1 for 5 and 2 for 9 blocks per block
+    pub southern: bool,   // block numbers count south from the equator
+    pub bins: NexradBins,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum NexradBins {
+    /// 128 intensities from 0 to 7, 32 bins per row from west to east, in 4 rows from north to south.
+    Intensities(Vec<u8>),
+    /// Block numbers of blocks with no precipitation, including `block_number`.
+    Empty(Vec<u32>),
+}
+
+impl Default for NexradBins {
+    fn default() -> Self {
+        NexradBins::Empty(Vec::new())
+    }
+}
+
+impl UatUplinkMessage {
+    /// Decodes a UAT uplink payload, without its Reed-Solomon parity.
+    pub fn from_payload(payload: &[u8]) -> MessageResult<UatUplinkMessage> {
+        if payload.len() != UAT_UPLINK_LENGTH {
+            return Err(message_error(format!(
+                "UAT uplink payload has an invalid length of {} bytes",
+                payload.len()
+            )));
+        }
+        let position: Option<UatPosition> = match payload[5] & 0x01 != 0 {
+            false => None,
+            true => {
+                let (raw_lat, raw_lon) = raw_lat_lon(&payload[..6]);
+                Some(decode_lat_lon(raw_lat, raw_lon))
+            }
+        };
+        let mut info_frames: Vec<FisbInfoFrame> = Vec::new();
+        if payload[6] & 0x20 != 0 {
+            let mut data: &[u8] = &payload[UAT_UPLINK_HEADER_LENGTH..];
+            while data.len() > 2 {
+                let length: usize = (data[0] as usize) << 1 | (data[1] as usize) >> 7;
+                let frame_type: u8 = data[1] & 0x0f;
+                if length == 0 || data.len() < length + 2 {
+                    break;
+                }
+                let frame: &[u8] = &data[2..length + 2];
+                info_frames.push(match frame_type {
+                    0 => FisbInfoFrame::Apdu(FisbApdu::from_bytes(frame)?),
+                    _ => FisbInfoFrame::Other {
+                        frame_type,
+                        data: frame.to_vec(),
+                    },
+                });
+                data = &data[length + 2..];
+            }
+        }
+        Ok(UatUplinkMessage {
+            metadata: None,
+            position,
+            utc_coupled: payload[6] & 0x80 != 0,
+            slot_id: payload[6] & 0x1f,
+            tisb_site_id: payload[7] >> 4,
+            info_frames,
+        })
+    }
+}
+
+impl FisbApdu {
+    /// Decodes the contents of an information frame of type 0.
+    pub fn from_bytes(frame: &[u8]) -> MessageResult<FisbApdu> {
+        let truncated = || message_error(format!("Truncated FIS-B APDU: {:02x?}", frame));
+        if frame.len() < 4 {
+            return Err(truncated());
+        }
+        let product_id: u16 = (frame[0] as u16 & 0x1f) << 6 | (frame[1] as u16) >> 2;
+        let segmented: bool = frame[1] & 0x02 != 0;
+        let (time, header_length): (FisbTime, usize) = match (frame[1] & 0x01) << 1 | frame[2] >> 7
+        {
+            0 => (
+                FisbTime {
+                    hours: (frame[2] & 0x7c) >> 2,
+                    minutes: (frame[2] & 0x03) << 4 | frame[3] >> 4,
+                    ..Default::default()
+                },
+                4,
+            ),
+            1 if frame.len() >= 5 => (
+                FisbTime {
+                    hours: (frame[2] & 0x7c) >> 2,
+                    minutes: (frame[2] & 0x03) << 4 | frame[3] >> 4,
+                    seconds: Some((frame[3] & 0x0f) << 2 | frame[4] >> 6),
+                    ..Default::default()
+                },
+                5,
+            ),
+            2 if frame.len() >= 5 => (
+                FisbTime {
+                    month: Some((frame[2] & 0x78) >> 3),
+                    day: Some((frame[2] & 0x07) << 2 | frame[3] >> 6),
+                    hours: (frame[3] & 0x3e) >> 1,
+                    minutes: (frame[3] & 0x01) << 5 | frame[4] >> 3,
+                    seconds: None,
+                },
+                5,
+            ),
+            3 if frame.len() >= 6 => (
+                FisbTime {
+                    month: Some((frame[2] & 0x78) >> 3),
+                    day: Some((frame[2] & 0x07) << 2 | frame[3] >> 6),
+                    hours: (frame[3] & 0x3e) >> 1,
+                    minutes: (frame[3] & 0x01) << 5 | frame[4] >> 3,
+                    seconds: Some((frame[4] & 0x07) << 3 | frame[5] >> 5),
+                },
+                6,
+            ),
+            _ => return Err(truncated()),
+        };
+        let data: &[u8] = &frame[header_length..];
+        let product: FisbProduct = match (product_id, segmented) {
+            (FISB_PRODUCT_TEXT, false) => FisbProduct::Text(decode_text_records(data)),
+            (FISB_PRODUCT_NEXRAD_REGIONAL | FISB_PRODUCT_NEXRAD_CONUS, false) => {
+                FisbProduct::Nexrad(NexradBlock::from_bytes(data)?)
+            }
+            _ => FisbProduct::Unsupported(data.to_vec()),
+        };
+        Ok(FisbApdu {
+            product_id,
+            a_flag: frame[0] & 0x80 != 0,
+            g_flag: frame[0] & 0x40 != 0,
+            p_flag: frame[0] & 0x20 != 0,
+            segmented,
+            time,
+            product,
+        })
+    }
+}
+
+impl NexradBlock {
+    /// Decodes the payload of a NEXRAD product, which is either a run length encoded block or a set of empty blocks.
+    pub fn from_bytes(data: &[u8]) -> MessageResult<NexradBlock> {
+        if data.len() < 4 {
+            return Err(message_error(format!(
+                "Truncated NEXRAD block: {:02x?}",
+                data
+            )));
+        }
+        let block_number: u32 =
+            (data[0] as u32 & 0x0f) << 16 | (data[1] as u32) << 8 | data[2] as u32;
+        let bins: NexradBins = match data[0] & 0x80 != 0 {
+            true => {
+                let mut intensities: Vec<u8> = Vec::with_capacity(128);
+                for bin in &data[3..] {
+                    let run_length: usize = (bin >> 3) as usize + 1;
+                    intensities.extend(std::iter::repeat_n(bin & 0x07, run_length));
+                }
+                intensities.resize(128, 0);
+                NexradBins::Intensities(intensities)
+            }
+            false => {
+                let (row_start, row_size): (u32, u32) = match block_number {
+                    number if number >= NEXRAD_POLAR_BLOCK_START => (
+                        number - (number - NEXRAD_POLAR_BLOCK_START) % (NEXRAD_BLOCKS_PER_ROW / 2),
+                        NEXRAD_BLOCKS_PER_ROW / 2,
+                    ),
+                    number => (
+                        number - number % NEXRAD_BLOCKS_PER_ROW,
+                        NEXRAD_BLOCKS_PER_ROW,
+                    ),
+                };
+                // The low nibble of the first bitmap byte is the bitmap length, so bit 3 stands in for the block itself.
+                let bitmap_length: usize = (data[3] & 0x0f) as usize;
+                let mut empty_blocks: Vec<u32> = vec![block_number];
+                for index in 0..bitmap_length {
+                    let bits: u8 = match index {
+                        0 => data[3] & 0xf0,
+                        _ => data.get(index + 3).copied().unwrap_or_default(),
+                    };
+                    for bit in (0..8).filter(|bit| bits & (1 << bit) != 0) {
+                        let offset: u32 =
+                            (block_number - row_start + 8 * index as u32 + bit - 3) % row_size;
+                        empty_blocks.push(row_start + offset);
+                    }
+                }
+                NexradBins::Empty(empty_blocks)
+            }
+        };
+        Ok(NexradBlock {
+            block_number,
+            scale_factor: (data[0] & 0x30) >> 4,
+            southern: data[0] & 0x40 != 0,
+            bins,
+        })
+    }
+}
+
+/// Unpacks DLAC text, four 6 bit characters to every three bytes.
+pub fn decode_dlac(data: &[u8]) -> String {
+    let mut text: String = String::new();
+    let mut is_tab: bool = false;
+    let characters = data.chunks(3).flat_map(|chunk| {
+        let bits: u32 = chunk
+            .iter()
+            .chain(std::iter::repeat(&0))
+            .take(3)
+            .fold(0, |bits, byte| bits << 8 | *byte as u32);
+        let count: usize = chunk.len() * 8 / 6;
+        (0..count).map(move |index| ((bits >> (18 - 6 * index)) & 0x3f) as u8)
+    });
+    for character in characters {
+        match (is_tab, character) {
+            (true, spaces) => {
+                text.extend(std::iter::repeat_n(' ', spaces as usize));
+                is_tab = false;
+            }
+            (false, DLAC_TAB) => is_tab = true,
+            (false, character) => text.push(DLAC_ALPHABET[character as usize] as char),
+        }
+    }
+    text
+}
+
+/// Splits a DLAC text product into its records, leaving out the end of text and padding characters.
+fn decode_text_records(data: &[u8]) -> Vec<FisbTextRecord> {
+    decode_dlac(data)
+        .split(DLAC_RECORD_SEPARATOR)
+        .map(|record| record.replace(['\x03', '\x1a'], "").trim().to_string())
+        .filter(|record| !record.is_empty())
+        .map(|text| {
+            let mut words = text.split_whitespace();
+            FisbTextRecord {
+                record_type: words.next().unwrap_or_default().to_string(),
+                location: words.next().unwrap_or_default().to_string(),
+                text: text.clone(),
+            }
+        })
+        .collect()
+}
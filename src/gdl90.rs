@@ -0,0 +1,378 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude};
+use serde::{Deserialize, Serialize};
+
+/// Flag byte that starts and ends every GDL90 frame.
+pub const GDL90_FLAG: u8 = 0x7e;
+
+/// Control escape byte used to stuff flag and escape bytes inside a frame.
+pub const GDL90_ESCAPE: u8 = 0x7d;
+
+/// Message ID of the heartbeat.
+pub const GDL90_HEARTBEAT_ID: u8 = 0;
+
+/// Message ID of the ownship report.
+pub const GDL90_OWNSHIP_ID: u8 = 10;
+
+/// Message ID of the ownship geometric altitude.
+pub const GDL90_OWNSHIP_GEOMETRIC_ALTITUDE_ID: u8 = 11;
+
+/// Message ID of the traffic report.
+pub const GDL90_TRAFFIC_ID: u8 = 20;
+
+/// Degrees per least significant bit of the 24 bit latitude and longitude.
+const GDL90_LAT_LON_RESOLUTION: f64 = 180.0 / 8_388_608.0;
+
+/// Degrees per least significant bit of the 8 bit track.
+const GDL90_TRACK_RESOLUTION: f32 = 360.0 / 256.0;
+
+/// Seconds in a day, the heartbeat timestamp counts the seconds since 0000Z.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Value of the 12 bit altitude when it is not available.
+const ALTITUDE_UNAVAILABLE: u16 = 0xfff;
+
+/// Value of the 12 bit horizontal velocity when it is not available.
+const HORIZONTAL_VELOCITY_UNAVAILABLE: u16 = 0xfff;
+
+/// Value of the 12 bit vertical velocity when it is not available.
+const VERTICAL_VELOCITY_UNAVAILABLE: u16 = 0x800;
+
+/// Value of the vertical figure of merit when it is not available.
+const VERTICAL_FIGURE_OF_MERIT_UNAVAILABLE: u16 = 0x7fff;
+
+/// CRC-CCITT lookup table, built as in the GDL90 specification.
+const GDL90_CRC_TABLE: [u16; 256] = build_crc_table();
+
+const fn build_crc_table() -> [u16; 256] {
+    let mut table: [u16; 256] = [0; 256];
+    let mut index: usize = 0;
+    while index < 256 {
+        let mut crc: u16 = (index as u16) << 8;
+        let mut bit: usize = 0;
+        while bit < 8 {
+            crc = (crc << 1) ^ if crc & 0x8000 != 0 { 0x1021 } else { 0 };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+}
+
+/// Computes the GDL90 CRC-CCITT of a message ID and its data, before byte stuffing.
+pub fn gdl90_crc(message: &[u8]) -> u16 {
+    message.iter().fold(0u16, |crc, byte| {
+        GDL90_CRC_TABLE[(crc >> 8) as usize] ^ (crc << 8) ^ *byte as u16
+    })
+}
+
+/// Frames a message ID and its data for sending, usually as a UDP datagram to port 4000.
+///
+/// The CRC is appended least significant byte first, flag and escape bytes are stuffed, and the frame is wrapped in flag bytes.
+pub fn gdl90_frame(message_id: u8, data: &[u8]) -> Vec<u8> {
+    let mut message: Vec<u8> = Vec::with_capacity(data.len() + 3);
+    message.push(message_id);
+    message.extend_from_slice(data);
+    let crc: u16 = gdl90_crc(&message);
+    message.extend_from_slice(&crc.to_le_bytes());
+    let mut frame: Vec<u8> = Vec::with_capacity(message.len() + 4);
+    frame.push(GDL90_FLAG);
+    for byte in message {
+        match byte {
+            GDL90_FLAG | GDL90_ESCAPE => {
+                frame.push(GDL90_ESCAPE);
+                frame.push(byte ^ 0x20);
+            }
+            _ => frame.push(byte),
+        }
+    }
+    frame.push(GDL90_FLAG);
+    frame
+}
+
+/// A GDL90 heartbeat, which EFB apps expect once a second to consider the device connected.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gdl90Heartbeat {
+    pub gps_position_valid: bool,   // ownship position is valid
+    pub maintenance_required: bool, // the device needs maintenance
+    pub ident: bool,                // ident is active
+    pub address_talkback: bool,     // ownship address type talkback
+    pub gps_battery_low: bool,      // GPS battery is low
+    pub ratcs: bool,                // ATC services talkback
+    pub uat_initialized: bool,      // the device is initialized
+    pub csa_requested: bool,        // conflict situational awareness requested
+    pub csa_not_available: bool,    // conflict situational awareness not available
+    pub utc_ok: bool,               // the timestamp is valid UTC
+    pub timestamp: u32,             // seconds since 0000Z, 17 bits
+    pub uplink_count: u8,           // uplink messages received in the last second, 5 bits
+    pub basic_long_count: u16,      // basic and long messages received in the last second, 10 bits
+}
+
+impl Gdl90Heartbeat {
+    /// Builds a heartbeat for a Unix timestamp in seconds, such as `now`, for a receiver without a GPS position.
+    pub fn from_unix_timestamp(now: f64) -> Gdl90Heartbeat {
+        Gdl90Heartbeat {
+            uat_initialized: true,
+            utc_ok: true,
+            timestamp: (now.max(0.0) as u64 % SECONDS_PER_DAY) as u32,
+            ..Default::default()
+        }
+    }
+
+    /// Converts `Gdl90Heartbeat` to its six data bytes, without the message ID or framing.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let status_1: u8 = (self.gps_position_valid as u8) << 7
+            | (self.maintenance_required as u8) << 6
+            | (self.ident as u8) << 5
+            | (self.address_talkback as u8) << 4
+            | (self.gps_battery_low as u8) << 3
+            | (self.ratcs as u8) << 2
+            | self.uat_initialized as u8;
+        let status_2: u8 = (((self.timestamp >> 16) & 1) as u8) << 7
+            | (self.csa_requested as u8) << 6
+            | (self.csa_not_available as u8) << 5
+            | self.utc_ok as u8;
+        let counts: u16 =
+            ((self.uplink_count.min(0x1f) as u16) << 11) | self.basic_long_count.min(0x3ff);
+        let timestamp: [u8; 2] = (self.timestamp as u16).to_le_bytes();
+        let counts: [u8; 2] = counts.to_be_bytes();
+        vec![
+            status_1,
+            status_2,
+            timestamp[0],
+            timestamp[1],
+            counts[0],
+            counts[1],
+        ]
+    }
+
+    /// Converts `Gdl90Heartbeat` to a complete frame.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        gdl90_frame(GDL90_HEARTBEAT_ID, &self.to_payload())
+    }
+}
+
+/// A GDL90 traffic report, which shares its layout with the ownship report.
+///
+/// Altitudes are in feet, speeds in knots and vertical rates in feet per minute.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Gdl90TrafficReport {
+    pub traffic_alert: bool,              // the target is a traffic alert
+    pub address_type: u8, // 0 ADS-B ICAO, 1 ADS-B self-assigned, 2 TIS-B ICAO, 3 TIS-B track file
+    pub address: u32,     // 24 bit participant address
+    pub lat: f64,         // latitude
+    pub lon: f64,         // longitude
+    pub altitude: Option<i32>, // pressure altitude, feet
+    pub airborne: bool,   // the target is airborne
+    pub extrapolated: bool, // the position is extrapolated rather than updated
+    pub track_type: u8,   // 0 invalid, 1 true track, 2 magnetic heading, 3 true heading
+    pub nic: u8,          // Navigation Integrity Category
+    pub nac_p: u8,        // Navigation Accuracy Category for Position
+    pub horizontal_velocity: Option<u16>, // ground speed, knots
+    pub vertical_velocity: Option<i32>, // vertical rate, feet per minute
+    pub track: Option<f32>, // track or heading, as set by track_type
+    pub emitter_category: u8, // emitter category, A1 is 1, B1 is 9 and C1 is 17
+    pub callsign: String, // callsign, up to 8 characters
+    pub emergency: u8,    // emergency/priority code
+}
+
+impl Gdl90TrafficReport {
+    /// Packs an `ADSBJsonMessage` into a GDL90 traffic report, rounding the position to 24 bit angles and the altitude to 25 ft steps.
+    ///
+    /// Returns `None` if the aircraft has no position or the address is not hexadecimal.
+    /// The callsign falls back to the registration, as EFB apps display it as the label of the target.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> Option<Gdl90TrafficReport> {
+        let (lat, lon) = message.position()?;
+        let non_icao: bool = message.hex.starts_with('~');
+        let address: u32 = u32::from_str_radix(message.hex.trim_start_matches('~'), 16).ok()?;
+        let address_type: u8 = match (message.adsb_type.as_str(), non_icao) {
+            ("tisb_icao", _) => 2,
+            ("tisb_other" | "tisb_trackfile", _) | ("mlat" | "mode_s", true) => 3,
+            ("adsb_other" | "adsr_other", _) | (_, true) => 1,
+            _ => 0,
+        };
+        let (altitude, airborne) = match &message.alt_baro {
+            Some(Altitude::I32(altitude)) => (Some(*altitude), true),
+            Some(Altitude::Ground(_)) => (None, false),
+            None => (None, true),
+        };
+        let (track, track_type) = match (message.track, message.true_heading, message.mag_heading) {
+            (Some(track), _, _) => (Some(track), 1),
+            (None, Some(heading), _) => (Some(heading), 3),
+            (None, None, Some(heading)) => (Some(heading), 2),
+            (None, None, None) => (None, 0),
+        };
+        let callsign: String = match message.flight.as_deref().map(str::trim) {
+            Some(flight) if !flight.is_empty() => flight.to_string(),
+            _ => message.aircraft_registration.replace('-', ""),
+        };
+        Some(Gdl90TrafficReport {
+            traffic_alert: false,
+            address_type,
+            address,
+            lat: lat as f64,
+            lon: lon as f64,
+            altitude,
+            airborne,
+            extrapolated: false,
+            track_type,
+            nic: message.nic.unwrap_or_default().clamp(0, 15) as u8,
+            nac_p: message.nac_p.unwrap_or_default().clamp(0, 15) as u8,
+            horizontal_velocity: message.gs.map(|gs| gs.round() as u16),
+            vertical_velocity: message.baro_rate.or(message.geom_rate),
+            track,
+            emitter_category: message
+                .category
+                .as_deref()
+                .map(emitter_category)
+                .unwrap_or_default(),
+            callsign,
+            emergency: message
+                .emergency
+                .as_deref()
+                .map(emergency_code)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Converts `Gdl90TrafficReport` to its 27 data bytes, without the message ID or framing.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let mut payload: Vec<u8> = Vec::with_capacity(27);
+        payload.push((self.traffic_alert as u8) << 4 | (self.address_type & 0x0f));
+        payload.extend_from_slice(&self.address.to_be_bytes()[1..]);
+        payload.extend_from_slice(&pack_lat_lon(self.lat));
+        payload.extend_from_slice(&pack_lat_lon(self.lon));
+        let altitude: u16 = match self.altitude {
+            None => ALTITUDE_UNAVAILABLE,
+            Some(altitude) => ((altitude + 1000) / 25).clamp(0, 0xffe) as u16,
+        };
+        let misc: u16 = (self.airborne as u16) << 3
+            | (self.extrapolated as u16) << 2
+            | (self.track_type & 0x03) as u16;
+        payload.extend_from_slice(&(altitude << 4 | misc).to_be_bytes());
+        payload.push((self.nic & 0x0f) << 4 | (self.nac_p & 0x0f));
+        let horizontal_velocity: u32 = match self.horizontal_velocity {
+            None => HORIZONTAL_VELOCITY_UNAVAILABLE as u32,
+            Some(velocity) => velocity.min(0xffe) as u32,
+        };
+        let vertical_velocity: u32 = match self.vertical_velocity {
+            None => VERTICAL_VELOCITY_UNAVAILABLE as u32,
+            Some(rate) => ((rate / 64).clamp(-510, 510) as u32) & 0xfff,
+        };
+        payload
+            .extend_from_slice(&(horizontal_velocity << 12 | vertical_velocity).to_be_bytes()[1..]);
+        payload.push(match self.track {
+            None => 0,
+            Some(track) => (track.rem_euclid(360.0) / GDL90_TRACK_RESOLUTION) as u8,
+        });
+        payload.push(self.emitter_category);
+        let mut callsign: Vec<u8> = self
+            .callsign
+            .bytes()
+            .filter(|byte| byte.is_ascii_alphanumeric() || *byte == b' ')
+            .map(|byte| byte.to_ascii_uppercase())
+            .take(8)
+            .collect();
+        callsign.resize(8, b' ');
+        payload.extend_from_slice(&callsign);
+        payload.push((self.emergency & 0x0f) << 4);
+        payload
+    }
+
+    /// Converts `Gdl90TrafficReport` to a complete traffic report frame.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        gdl90_frame(GDL90_TRAFFIC_ID, &self.to_payload())
+    }
+
+    /// Converts `Gdl90TrafficReport` to a complete ownship report frame, for a receiver that knows its own aircraft.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_ownship_bytes(&self) -> Vec<u8> {
+        gdl90_frame(GDL90_OWNSHIP_ID, &self.to_payload())
+    }
+}
+
+/// A GDL90 ownship geometric altitude, sent alongside the ownship report.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Gdl90OwnshipGeometricAltitude {
+    pub altitude: i32,                         // geometric altitude, feet
+    pub vertical_warning: bool,                // the vertical figure of merit is not reliable
+    pub vertical_figure_of_merit: Option<u16>, // vertical figure of merit, metres
+}
+
+impl Gdl90OwnshipGeometricAltitude {
+    /// Builds an ownship geometric altitude from an `ADSBJsonMessage`, with the figure of merit derived from `gva`.
+    ///
+    /// Returns `None` if the aircraft has no geometric altitude.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> Option<Gdl90OwnshipGeometricAltitude> {
+        Some(Gdl90OwnshipGeometricAltitude {
+            altitude: message.alt_geom?,
+            vertical_warning: false,
+            vertical_figure_of_merit: match message.gva {
+                Some(1) => Some(150),
+                Some(2) => Some(45),
+                _ => None,
+            },
+        })
+    }
+
+    /// Converts `Gdl90OwnshipGeometricAltitude` to its four data bytes, without the message ID or framing.
+    pub fn to_payload(&self) -> Vec<u8> {
+        let altitude: i16 = (self.altitude / 5).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        let metrics: u16 = (self.vertical_warning as u16) << 15
+            | self
+                .vertical_figure_of_merit
+                .unwrap_or(VERTICAL_FIGURE_OF_MERIT_UNAVAILABLE)
+                .min(VERTICAL_FIGURE_OF_MERIT_UNAVAILABLE);
+        let mut payload: Vec<u8> = altitude.to_be_bytes().to_vec();
+        payload.extend_from_slice(&metrics.to_be_bytes());
+        payload
+    }
+
+    /// Converts `Gdl90OwnshipGeometricAltitude` to a complete frame.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        gdl90_frame(GDL90_OWNSHIP_GEOMETRIC_ALTITUDE_ID, &self.to_payload())
+    }
+}
+
+/// Packs a latitude or longitude into 24 bit two's complement, truncating as the GDL90 specification does.
+fn pack_lat_lon(degrees: f64) -> [u8; 3] {
+    let packed: i32 = (degrees / GDL90_LAT_LON_RESOLUTION) as i32;
+    let bytes: [u8; 4] = packed.to_be_bytes();
+    [bytes[1], bytes[2], bytes[3]]
+}
+
+/// Converts an emitter category, such as A3, to the GDL90 emitter category number.
+fn emitter_category(category: &str) -> u8 {
+    let mut characters = category.chars();
+    let set: u8 = match characters.next() {
+        Some('A') => 0,
+        Some('B') => 8,
+        Some('C') => 16,
+        Some('D') => 24,
+        _ => return 0,
+    };
+    match characters.as_str().parse::<u8>() {
+        Ok(number) if number < 8 => set + number,
+        _ => 0,
+    }
+}
+
+/// Converts an emergency, as written in `ADSBJsonMessage`, to the GDL90 emergency/priority code.
+fn emergency_code(emergency: &str) -> u8 {
+    match emergency {
+        "general" => 1,
+        "lifeguard" => 2,
+        "minfuel" => 3,
+        "nordo" => 4,
+        "unlawful" => 5,
+        "downed" => 6,
+        _ => 0,
+    }
+}
@@ -4,18 +4,89 @@ extern crate serde;
 extern crate serde_json;
 
 use crate::adsb_json::ADSBJsonMessage;
+use crate::avr::NewAvrMessage;
+use crate::beast::{BeastMessage, NewBeastMessage, BEAST_ESCAPE};
+use crate::sbs::{is_sbs_line, NewSbsMessage, SbsMessage};
+use crate::uat_json::UatJsonMessage;
+use crate::uat_raw::NewUatRawMessage;
+use chrono::{NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
 pub mod adsb_json;
+pub mod aircraft_json;
+pub mod api_v2;
+pub mod asterix;
+pub mod avr;
+pub mod beast;
+pub mod bincraft;
+mod callsign;
+pub mod cot;
+pub mod cpr;
+pub mod faup;
+pub mod fisb;
+pub mod gdl90;
+pub mod modes;
+pub mod opensky;
+pub mod sbs;
+pub mod trace;
+pub mod uat_json;
+pub mod uat_raw;
+pub mod vrs;
 
 /// Common return type for all serialisation/deserialisation functions.
 ///
 /// This serves as a wrapper for `serde_json::Error` as the Error type.
 pub type MessageResult<T> = Result<T, serde_json::Error>;
 
+/// Metres in a foot.
+pub(crate) const METRES_PER_FOOT: f32 = 0.3048;
+/// Metres per second in a knot.
+pub(crate) const METRES_PER_SECOND_PER_KNOT: f32 = 0.514_444;
+/// Metres per second in a foot per minute.
+pub(crate) const METRES_PER_SECOND_PER_FOOT_PER_MINUTE: f32 = 0.005_08;
+
+/// Builds the `serde_json::Error` used by `MessageResult` for decoding failures that do not come from serde.
+pub(crate) fn message_error<T: Display>(message: T) -> serde_json::Error {
+    serde::de::Error::custom(message)
+}
+
+/// Converts a Unix timestamp in seconds, such as `now`, to a UTC date and time.
+pub(crate) fn timestamp_to_date_time(timestamp: f64) -> Option<NaiveDateTime> {
+    let milliseconds: i64 = (timestamp * 1000.0).round() as i64;
+    Utc.timestamp_opt(
+        milliseconds.div_euclid(1000),
+        (milliseconds.rem_euclid(1000) * 1_000_000) as u32,
+    )
+    .single()
+    .map(|date_time| date_time.naive_utc())
+}
+
+/// Decodes a string of hexadecimal digit pairs into bytes.
+pub(crate) fn decode_hex(hex: &str) -> MessageResult<Vec<u8>> {
+    if !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(message_error(format!("Invalid hex data: {}", hex)));
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err(message_error(format!(
+            "Hex data has an odd number of digits: {}",
+            hex
+        )));
+    }
+    Ok(hex
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let high: u32 = (pair[0] as char).to_digit(16).unwrap_or_default();
+            let low: u32 = (pair[1] as char).to_digit(16).unwrap_or_default();
+            (high << 4 | low) as u8
+        })
+        .collect())
+}
+
 /// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
 ///
-/// The originating data must be in JSON format and have support for providing a `str`, and will not consume the source.
+/// The originating data must be in JSON, UAT JSON, raw UAT, AVR, SBS or Beast format, and will not consume the source.
 pub trait DecodeMessage {
     fn decode_message(&self) -> MessageResult<ADSBMessage>;
 }
@@ -25,18 +96,53 @@ pub trait DecodeMessage {
 /// This does not consume the `String`.
 impl DecodeMessage for String {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
-        serde_json::from_str(self)
+        self.as_str().decode_message()
     }
 }
 
 /// Provides functionality for decoding a `str` to `ADSBMessage`.
 ///
+/// AVR lines, starting with `*` or `@`, are decoded as raw frames.
+/// Raw UAT downlink lines, starting with `-`, are decoded as UAT messages.
+/// BaseStation lines, starting with a record type such as `MSG,`, are decoded as SBS messages.
+/// Everything else is decoded as JSON, either a readsb aircraft record or a dump978-fa UAT message.
 /// This does not consume the `str`.
 impl DecodeMessage for str {
     fn decode_message(&self) -> MessageResult<ADSBMessage> {
-        serde_json::from_str(self)
+        match self.trim_start().chars().next() {
+            Some('*') | Some('@') => self.to_avr().map(ADSBMessage::Beast),
+            Some('-') => self.to_uat_raw().map(ADSBMessage::Uat),
+            _ if is_sbs_line(self) => self.to_sbs().map(ADSBMessage::Sbs),
+            _ => serde_json::from_str(self),
+        }
+    }
+}
+
+/// Provides functionality for decoding a `Vec<u8>` to `ADSBMessage`.
+///
+/// This does not consume the `Vec<u8>`.
+impl DecodeMessage for Vec<u8> {
+    fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        self.as_slice().decode_message()
     }
 }
+
+/// Provides functionality for decoding a `[u8]` to `ADSBMessage`.
+///
+/// Data starting with the Beast escape byte is decoded as a single Beast frame, anything else is decoded as text.
+/// This does not consume the `[u8]`.
+impl DecodeMessage for [u8] {
+    fn decode_message(&self) -> MessageResult<ADSBMessage> {
+        match self.first() {
+            Some(&BEAST_ESCAPE) => self.to_beast().map(ADSBMessage::Beast),
+            _ => match std::str::from_utf8(self) {
+                Err(utf8_error) => Err(message_error(utf8_error)),
+                Ok(text) => text.decode_message(),
+            },
+        }
+    }
+}
+
 /// Implementation of `ADSBMessage`.
 impl ADSBMessage {
     /// Converts `ADSBMessage` to `String`.
@@ -80,7 +186,7 @@ impl ADSBMessage {
     }
 }
 
-/// This will automagically serialise JSON or (TODO: BEAST).
+/// This will automagically serialise JSON, UAT JSON, SBS or BEAST.
 ///
 /// This simplifies the handling of messaging by not needing to identify it first.
 /// It handles identification by looking at the provided data and seeing which format matches it best.
@@ -89,6 +195,9 @@ impl ADSBMessage {
 #[serde(untagged)]
 pub enum ADSBMessage {
     ADSBJsonMessage(ADSBJsonMessage),
+    Beast(BeastMessage),
+    Sbs(SbsMessage),
+    Uat(UatJsonMessage),
 }
 
 impl Default for ADSBMessage {
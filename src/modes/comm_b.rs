@@ -0,0 +1,390 @@
+use crate::callsign::decode_callsign;
+use crate::modes::{bit, bits};
+use serde::{Deserialize, Serialize};
+
+/// Length in bytes of the MB field carried by DF20 and DF21 replies.
+pub const MB_LENGTH: usize = 7;
+
+/// Registers reported as supported by BDS 1,7, in bit order.
+const GICB_REGISTERS: [&str; 24] = [
+    "0,5", "0,6", "0,7", "0,8", "0,9", "0,A", "2,0", "2,1", "4,0", "4,1", "4,2", "4,3", "4,4",
+    "4,5", "4,8", "5,0", "5,1", "5,2", "5,3", "5,4", "5,5", "5,6", "5,F", "6,0",
+];
+
+/// Highest plausible airspeed in a Comm-B reply, knots.
+const MAXIMUM_AIRSPEED: u32 = 600;
+
+/// Highest plausible vertical rate in a Comm-B reply, feet/minute.
+const MAXIMUM_VERTICAL_RATE: i32 = 6000;
+
+/// The MB field of a Comm-B reply, decoded as the BDS register it most likely holds.
+///
+/// The register number is not sent with the reply, so it is inferred from the fixed header bits of
+/// BDS 1,0, 2,0 and 3,0 and from the reserved bits, status bits and plausible ranges of the others.
+/// Fields that fit no register, or more than one, are kept as the raw MB field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CommB {
+    DataLinkCapability(DataLinkCapability),
+    GicbCapability(Vec<String>), // BDS 1,7, registers supported, such as 4,0
+    Identification(String),      // BDS 2,0, callsign padded with spaces
+    AcasResolutionAdvisory(AcasResolutionAdvisory),
+    SelectedVerticalIntention(SelectedVerticalIntention),
+    Meteorological(Meteorological),
+    TrackAndTurn(TrackAndTurn),
+    HeadingAndSpeed(HeadingAndSpeed),
+    Unknown(Vec<u8>),
+}
+
+/// BDS 1,0, data link capability report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DataLinkCapability {
+    pub overlay_command: bool,         // overlay command capability
+    pub acas: bool,                    // ACAS is fitted
+    pub subnetwork_version: u8,        // Mode S subnetwork version number
+    pub enhanced_protocol: bool,       // level 5 transponder
+    pub specific_services: bool,       // Mode S specific services capability
+    pub aircraft_identification: bool, // aircraft identification capability
+    pub squitter: bool,                // extended squitter capability
+    pub surveillance_identifier: bool, // surveillance identifier code capability
+    pub common_usage_gicb: bool,       // BDS 1,7 has changed
+}
+
+/// BDS 3,0, ACAS active resolution advisory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AcasResolutionAdvisory {
+    pub active_advisories: u16, // ARA, 14 bits
+    pub complements: u8,        // RAC, 4 bits
+    pub terminated: bool,       // the advisory has ended
+    pub multiple_threats: bool, // more than one threat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threat_address: Option<u32>, // ICAO address of the threat, when identified by address
+}
+
+/// BDS 4,0, selected vertical intention.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SelectedVerticalIntention {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mcp_altitude: Option<i32>, // MCP/FCU selected altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fms_altitude: Option<i32>, // FMS selected altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baro_setting: Option<f32>, // altimeter setting, hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vnav: Option<bool>, // VNAV mode, when the mode bits are valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude_hold: Option<bool>, // altitude hold mode, when the mode bits are valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approach: Option<bool>, // approach mode, when the mode bits are valid
+}
+
+/// BDS 4,4, meteorological routine air report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Meteorological {
+    pub source: u8,          // figure of merit, the source of the data
+    pub wind_speed: u32,     // knots
+    pub wind_direction: f32, // degrees the wind blows from, clockwise from true north
+    pub temperature: f32,    // static air temperature, C
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<u32>, // static pressure, hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turbulence: Option<u8>, // 0 nil to 3 severe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<f32>, // percent
+}
+
+/// BDS 5,0, track and turn report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrackAndTurn {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roll: Option<f32>, // degrees, negative is left roll
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<f32>, // true track, degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<u32>, // knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_rate: Option<f32>, // degrees/second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_airspeed: Option<u32>, // knots
+}
+
+/// BDS 6,0, heading and speed report.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HeadingAndSpeed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub magnetic_heading: Option<f32>, // degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indicated_airspeed: Option<u32>, // knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mach: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baro_rate: Option<i32>, // barometric altitude rate, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inertial_rate: Option<i32>, // inertial vertical velocity, feet/minute
+}
+
+impl CommB {
+    /// Infers the register held by a 7 byte MB field and decodes it. Bits are numbered from 1, as in ICAO Doc 9871.
+    ///
+    /// Returns `None` for an empty field, all zeros, which any register could hold.
+    pub fn decode(mb: &[u8]) -> Option<CommB> {
+        if mb.len() != MB_LENGTH {
+            return Some(CommB::Unknown(mb.to_vec()));
+        }
+        if mb.iter().all(|byte| *byte == 0) {
+            return None;
+        }
+        let candidates: Vec<CommB> = [
+            decode_bds10(mb).map(CommB::DataLinkCapability),
+            decode_bds17(mb).map(CommB::GicbCapability),
+            decode_bds20(mb).map(CommB::Identification),
+            decode_bds30(mb).map(CommB::AcasResolutionAdvisory),
+            decode_bds40(mb).map(CommB::SelectedVerticalIntention),
+            decode_bds44(mb).map(CommB::Meteorological),
+            decode_bds50(mb).map(CommB::TrackAndTurn),
+            decode_bds60(mb).map(CommB::HeadingAndSpeed),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        match candidates.len() {
+            1 => candidates.into_iter().next(),
+            0 => Some(CommB::Unknown(mb.to_vec())),
+            _ => {
+                debug!(
+                    "Comm-B field {:02x?} fits registers {:?}",
+                    mb,
+                    candidates.iter().map(CommB::bds).collect::<Vec<_>>()
+                );
+                Some(CommB::Unknown(mb.to_vec()))
+            }
+        }
+    }
+
+    /// Returns the inferred register, such as 4,0, or `None` if it is unknown.
+    pub fn bds(&self) -> Option<&'static str> {
+        match self {
+            CommB::DataLinkCapability(_) => Some("1,0"),
+            CommB::GicbCapability(_) => Some("1,7"),
+            CommB::Identification(_) => Some("2,0"),
+            CommB::AcasResolutionAdvisory(_) => Some("3,0"),
+            CommB::SelectedVerticalIntention(_) => Some("4,0"),
+            CommB::Meteorological(_) => Some("4,4"),
+            CommB::TrackAndTurn(_) => Some("5,0"),
+            CommB::HeadingAndSpeed(_) => Some("6,0"),
+            CommB::Unknown(_) => None,
+        }
+    }
+}
+
+fn decode_bds10(mb: &[u8]) -> Option<DataLinkCapability> {
+    if bits(mb, 1, 8) != 0x10 || bits(mb, 10, 14) != 0 {
+        return None;
+    }
+    Some(DataLinkCapability {
+        overlay_command: bit(mb, 15),
+        acas: bit(mb, 16),
+        subnetwork_version: bits(mb, 17, 23) as u8,
+        enhanced_protocol: bit(mb, 24),
+        specific_services: bit(mb, 25),
+        aircraft_identification: bit(mb, 33),
+        squitter: bit(mb, 34),
+        surveillance_identifier: bit(mb, 35),
+        common_usage_gicb: bit(mb, 36),
+    })
+}
+
+fn decode_bds17(mb: &[u8]) -> Option<Vec<String>> {
+    // Every aircraft reporting its capabilities supports identification, BDS 2,0.
+    if bits(mb, 25, 56) != 0 || !bit(mb, 7) {
+        return None;
+    }
+    Some(
+        GICB_REGISTERS
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| bit(mb, index + 1))
+            .map(|(_, register)| register.to_string())
+            .collect(),
+    )
+}
+
+fn decode_bds20(mb: &[u8]) -> Option<String> {
+    if bits(mb, 1, 8) != 0x20 {
+        return None;
+    }
+    let callsign: String = decode_callsign(&mb[1..]);
+    match callsign.contains('#') || callsign.trim().is_empty() {
+        true => None,
+        false => Some(callsign),
+    }
+}
+
+fn decode_bds30(mb: &[u8]) -> Option<AcasResolutionAdvisory> {
+    let threat_type: u32 = bits(mb, 29, 30);
+    if bits(mb, 1, 8) != 0x30 || threat_type == 3 {
+        return None;
+    }
+    Some(AcasResolutionAdvisory {
+        active_advisories: bits(mb, 9, 22) as u16,
+        complements: bits(mb, 23, 26) as u8,
+        terminated: bit(mb, 27),
+        multiple_threats: bit(mb, 28),
+        threat_address: match threat_type {
+            1 => Some(bits(mb, 31, 54)),
+            _ => None,
+        },
+    })
+}
+
+fn decode_bds40(mb: &[u8]) -> Option<SelectedVerticalIntention> {
+    if bits(mb, 40, 47) != 0 || bits(mb, 52, 53) != 0 {
+        return None;
+    }
+    let mcp_altitude: Option<u32> = status_field(mb, 1, 2, 13)?;
+    let fms_altitude: Option<u32> = status_field(mb, 14, 15, 26)?;
+    let baro_setting: Option<u32> = status_field(mb, 27, 28, 39)?;
+    let modes: Option<u32> = status_field(mb, 48, 49, 51)?;
+    status_field(mb, 54, 55, 56)?;
+    if mcp_altitude.is_none() && fms_altitude.is_none() && baro_setting.is_none() {
+        return None;
+    }
+    let mcp_altitude: Option<i32> = mcp_altitude.map(|altitude| altitude as i32 * 16);
+    let fms_altitude: Option<i32> = fms_altitude.map(|altitude| altitude as i32 * 16);
+    let baro_setting: Option<f32> = baro_setting.map(|setting| 800.0 + setting as f32 * 0.1);
+    if [mcp_altitude, fms_altitude]
+        .into_iter()
+        .flatten()
+        .any(|altitude| altitude > 50_000)
+        || baro_setting.is_some_and(|setting| !(900.0..=1100.0).contains(&setting))
+    {
+        return None;
+    }
+    Some(SelectedVerticalIntention {
+        mcp_altitude,
+        fms_altitude,
+        baro_setting,
+        vnav: modes.map(|modes| modes & 0x4 != 0),
+        altitude_hold: modes.map(|modes| modes & 0x2 != 0),
+        approach: modes.map(|modes| modes & 0x1 != 0),
+    })
+}
+
+fn decode_bds44(mb: &[u8]) -> Option<Meteorological> {
+    let source: u8 = bits(mb, 1, 4) as u8;
+    let wind_speed: u32 = status_field(mb, 5, 6, 14)??;
+    let wind_direction: f32 = bits(mb, 15, 23) as f32 * 180.0 / 256.0;
+    let temperature: f32 = signed_bits(mb, 24, 34) as f32 * 0.25;
+    let pressure: Option<u32> = status_field(mb, 35, 36, 46)?;
+    let turbulence: Option<u32> = status_field(mb, 47, 48, 49)?;
+    let humidity: Option<u32> = status_field(mb, 50, 51, 56)?;
+    if !(1..=4).contains(&source)
+        || wind_speed > 250
+        || !(-80.0..=60.0).contains(&temperature)
+        || pressure.is_some_and(|pressure| pressure > 1100)
+    {
+        return None;
+    }
+    Some(Meteorological {
+        source,
+        wind_speed,
+        wind_direction,
+        temperature,
+        pressure,
+        turbulence: turbulence.map(|turbulence| turbulence as u8),
+        humidity: humidity.map(|humidity| humidity as f32 * 100.0 / 64.0),
+    })
+}
+
+fn decode_bds50(mb: &[u8]) -> Option<TrackAndTurn> {
+    let roll: Option<f32> =
+        signed_status_field(mb, 1, 2, 11)?.map(|roll| roll as f32 * 45.0 / 256.0);
+    let track: Option<f32> = status_field(mb, 12, 13, 23)?.map(|track| track as f32 * 90.0 / 512.0);
+    let ground_speed: Option<u32> = status_field(mb, 24, 25, 34)?.map(|speed| speed * 2);
+    let track_rate: Option<f32> =
+        signed_status_field(mb, 35, 36, 45)?.map(|rate| rate as f32 * 8.0 / 256.0);
+    let true_airspeed: Option<u32> = status_field(mb, 46, 47, 56)?.map(|speed| speed * 2);
+    if roll.is_none() && track.is_none() && ground_speed.is_none() && true_airspeed.is_none() {
+        return None;
+    }
+    if roll.is_some_and(|roll| roll.abs() > 50.0)
+        || [ground_speed, true_airspeed]
+            .into_iter()
+            .flatten()
+            .any(|speed| speed == 0 || speed > MAXIMUM_AIRSPEED)
+        || track_rate.is_some_and(|rate| rate.abs() > 8.0)
+    {
+        return None;
+    }
+    if let (Some(ground_speed), Some(true_airspeed)) = (ground_speed, true_airspeed) {
+        if ground_speed.abs_diff(true_airspeed) > 200 {
+            return None;
+        }
+    }
+    Some(TrackAndTurn {
+        roll,
+        track,
+        ground_speed,
+        track_rate,
+        true_airspeed,
+    })
+}
+
+fn decode_bds60(mb: &[u8]) -> Option<HeadingAndSpeed> {
+    let magnetic_heading: Option<f32> =
+        status_field(mb, 1, 2, 12)?.map(|heading| heading as f32 * 90.0 / 512.0);
+    let indicated_airspeed: Option<u32> = status_field(mb, 13, 14, 23)?;
+    let mach: Option<f32> = status_field(mb, 24, 25, 34)?.map(|mach| mach as f32 * 0.004);
+    let baro_rate: Option<i32> = signed_status_field(mb, 35, 36, 45)?.map(|rate| rate * 32);
+    let inertial_rate: Option<i32> = signed_status_field(mb, 46, 47, 56)?.map(|rate| rate * 32);
+    if magnetic_heading.is_none() && indicated_airspeed.is_none() && mach.is_none() {
+        return None;
+    }
+    if indicated_airspeed.is_some_and(|speed| speed == 0 || speed > 500)
+        || mach.is_some_and(|mach| mach == 0.0 || mach > 1.0)
+        || [baro_rate, inertial_rate]
+            .into_iter()
+            .flatten()
+            .any(|rate| rate.abs() > MAXIMUM_VERTICAL_RATE)
+    {
+        return None;
+    }
+    // The speed of sound in knots of indicated airspeed falls from 661 at sea level to around 300 at cruising altitudes.
+    if let (Some(speed), Some(mach)) = (indicated_airspeed, mach) {
+        if !(150.0..=700.0).contains(&(speed as f32 / mach)) {
+            return None;
+        }
+    }
+    Some(HeadingAndSpeed {
+        magnetic_heading,
+        indicated_airspeed,
+        mach,
+        baro_rate,
+        inertial_rate,
+    })
+}
+
+/// Reads a field with a status bit, returning `Some(None)` when the status is clear and the field is zero,
+/// and `None` when the status is clear but the field is not, which no register allows.
+fn status_field(mb: &[u8], status: usize, first: usize, last: usize) -> Option<Option<u32>> {
+    let value: u32 = bits(mb, first, last);
+    match (bit(mb, status), value) {
+        (true, value) => Some(Some(value)),
+        (false, 0) => Some(None),
+        (false, _) => None,
+    }
+}
+
+/// Reads a two's complement field with a status bit, as `status_field` does.
+fn signed_status_field(mb: &[u8], status: usize, first: usize, last: usize) -> Option<Option<i32>> {
+    Some(status_field(mb, status, first, last)?.map(|_| signed_bits(mb, first, last)))
+}
+
+/// Reads bits `first` to `last` as a two's complement number.
+fn signed_bits(mb: &[u8], first: usize, last: usize) -> i32 {
+    let width: usize = last - first + 1;
+    let value: i32 = bits(mb, first, last) as i32;
+    match bit(mb, first) {
+        true => value - (1 << width),
+        false => value,
+    }
+}
@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+/// Mode-S CRC-24 generator polynomial, without the x^24 term.
+pub const MODES_GENERATOR: u32 = 0xfff409;
+
+/// Number of bits at the start of every frame holding the downlink format, which are never corrected.
+const DOWNLINK_FORMAT_BITS: usize = 5;
+
+/// Syndrome of a single bit error `k` bits before the end of a frame, x^k modulo the generator, for frames of up to 112 bits.
+const BIT_SYNDROMES: [u32; 112] = build_bit_syndromes();
+
+const fn build_bit_syndromes() -> [u32; 112] {
+    let mut table: [u32; 112] = [0; 112];
+    let mut syndrome: u32 = 1;
+    let mut k: usize = 0;
+    while k < 112 {
+        table[k] = syndrome;
+        syndrome <<= 1;
+        if syndrome & 0x100_0000 != 0 {
+            syndrome ^= 0x100_0000 | MODES_GENERATOR;
+        }
+        k += 1;
+    }
+    table
+}
+
+/// How many flipped bits to try to fix in a DF17 or DF18 frame that fails its CRC.
+///
+/// All-call replies (DF11) are never corrected by more than one bit, as readsb does.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    Disabled,
+    #[default]
+    SingleBit,
+    TwoBit,
+}
+
+impl ErrorCorrection {
+    fn max_bits(&self) -> usize {
+        match self {
+            ErrorCorrection::Disabled => 0,
+            ErrorCorrection::SingleBit => 1,
+            ErrorCorrection::TwoBit => 2,
+        }
+    }
+}
+
+/// How a decoded frame passed the CRC check.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum FrameQuality {
+    /// The CRC matched as received.
+    #[default]
+    Clean,
+    /// The CRC matched after flipping these bits, numbered from 0 at the first bit of the frame.
+    Corrected(Vec<usize>),
+    /// The address was recovered from the address/parity field, so the CRC could not be checked.
+    AddressParity,
+}
+
+impl FrameQuality {
+    /// Returns the number of bits flipped to make the CRC match.
+    pub fn corrected_bits(&self) -> usize {
+        match self {
+            FrameQuality::Corrected(bits) => bits.len(),
+            _ => 0,
+        }
+    }
+}
+
+/// Returns the CRC-24 of `data`, the remainder of `data` times x^24 modulo the generator.
+pub fn modes_checksum(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |crc, byte| {
+        (0..8).fold(crc ^ (*byte as u32) << 16, |crc, _| {
+            let crc: u32 = crc << 1;
+            match crc & 0x100_0000 != 0 {
+                true => crc ^ (0x100_0000 | MODES_GENERATOR),
+                false => crc,
+            }
+        })
+    })
+}
+
+/// Returns the CRC of a whole frame XORed with its last 3 bytes, the parity field.
+///
+/// This is 0 for an undamaged DF11, DF17 or DF18 frame, and the address for an undamaged frame with an address/parity field.
+pub fn modes_syndrome(frame: &[u8]) -> u32 {
+    if frame.len() < 4 {
+        return 0;
+    }
+    let (data, parity) = frame.split_at(frame.len() - 3);
+    modes_checksum(data) ^ (parity[0] as u32) << 16 ^ (parity[1] as u32) << 8 ^ parity[2] as u32
+}
+
+/// Finds the bits, numbered from 0 at the first bit of the frame, whose errors give `syndrome`.
+///
+/// Tries single bit errors, then pairs if `max_bits` allows, and gives up if more than one pair matches.
+/// Bits of the downlink format are never returned, as changing them would change the frame's length and meaning.
+pub fn find_bit_errors(frame_bits: usize, syndrome: u32, max_bits: usize) -> Option<Vec<usize>> {
+    if syndrome == 0 || max_bits == 0 || frame_bits > BIT_SYNDROMES.len() {
+        return None;
+    }
+    let candidates = DOWNLINK_FORMAT_BITS..frame_bits;
+    let bit_syndrome = |bit: usize| BIT_SYNDROMES[frame_bits - 1 - bit];
+    if let Some(bit) = candidates
+        .clone()
+        .find(|bit| bit_syndrome(*bit) == syndrome)
+    {
+        return Some(vec![bit]);
+    }
+    if max_bits < 2 {
+        return None;
+    }
+    let mut pairs = candidates.clone().flat_map(|first| {
+        (first + 1..frame_bits)
+            .filter(move |second| bit_syndrome(first) ^ bit_syndrome(*second) == syndrome)
+            .map(move |second| vec![first, second])
+    });
+    match (pairs.next(), pairs.next()) {
+        (Some(pair), None) => Some(pair),
+        _ => None,
+    }
+}
+
+/// Checks the CRC of a DF11, DF17 or DF18 frame, correcting up to the bits allowed by `correction`.
+///
+/// Returns the frame as it should be decoded and its quality, or `None` if the CRC cannot be made to match.
+/// DF11 replies to interrogators other than 0 carry the interrogator code in the low 7 bits of the syndrome, and are accepted as clean.
+pub fn check_frame(frame: &[u8], correction: ErrorCorrection) -> Option<(Vec<u8>, FrameQuality)> {
+    let downlink_format: u8 = frame.first()? >> 3;
+    let syndrome: u32 = modes_syndrome(frame);
+    if syndrome == 0 || (downlink_format == 11 && syndrome & !0x7f == 0) {
+        return Some((frame.to_vec(), FrameQuality::Clean));
+    }
+    let max_bits: usize = match downlink_format {
+        11 => correction.max_bits().min(1),
+        _ => correction.max_bits(),
+    };
+    let errors: Vec<usize> = find_bit_errors(frame.len() * 8, syndrome, max_bits)?;
+    let mut corrected: Vec<u8> = frame.to_vec();
+    for bit in &errors {
+        corrected[bit / 8] ^= 0x80 >> (bit % 8);
+    }
+    debug!(
+        "Corrected Mode-S DF{} frame at bits {:?}",
+        downlink_format, errors
+    );
+    Some((corrected, FrameQuality::Corrected(errors)))
+}
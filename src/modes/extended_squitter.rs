@@ -0,0 +1,348 @@
+use crate::adsb_json::NavModes;
+use crate::callsign::decode_callsign;
+use crate::modes::gillham::{decode_ac12, decode_id13};
+use crate::modes::{bit, bits};
+use serde::{Deserialize, Serialize};
+
+/// Length in bytes of the ME field carried by DF17 and DF18 frames.
+pub const ME_LENGTH: usize = 7;
+
+/// Emergency states of aircraft status messages, indexed by their code.
+const EMERGENCY_STATES: [&str; 8] = [
+    "none",
+    "general",
+    "lifeguard",
+    "minfuel",
+    "nordo",
+    "unlawful",
+    "downed",
+    "reserved",
+];
+
+/// The decoded ME field of an extended squitter, chosen by its type code.
+///
+/// Positions keep their raw CPR encoded latitude and longitude, which need a second frame or a reference position to resolve.
+/// Type codes and subtypes that are not decoded are kept as the raw ME field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ExtendedSquitter {
+    Identification(Identification),
+    SurfacePosition(SurfacePosition),
+    AirbornePosition(AirbornePosition),
+    AirborneVelocity(AirborneVelocity),
+    AircraftStatus(AircraftStatus),
+    TargetState(TargetState),
+    OperationalStatus(OperationalStatus),
+    Unsupported(Vec<u8>),
+}
+
+/// Aircraft identification and category, type codes 1 to 4.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Identification {
+    pub category: String, // emitter category, such as A3
+    pub callsign: String, // 8 characters, padded with spaces
+}
+
+/// Surface position, type codes 5 to 8.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SurfacePosition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<f32>, // lower bound of the movement field, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<f32>, // ground track, degrees clockwise from true north
+    pub utc_synchronised: bool, // T flag, the position time is synchronised to UTC
+    pub odd: bool,              // F flag, odd rather than even CPR format
+    pub lat_cpr: u32,           // 17 bit CPR encoded latitude
+    pub lon_cpr: u32,           // 17 bit CPR encoded longitude
+}
+
+/// Airborne position with barometric altitude, type codes 9 to 18, or GNSS height, type codes 20 to 22.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AirbornePosition {
+    pub surveillance_status: u8, // 0 none, 1 permanent alert, 2 temporary alert, 3 SPI
+    pub nic_supplement_b: bool,  // NIC supplement B, single antenna flag before version 2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>, // feet
+    pub gnss_height: bool,       // the altitude is GNSS height rather than barometric
+    pub utc_synchronised: bool,  // T flag, the position time is synchronised to UTC
+    pub odd: bool,               // F flag, odd rather than even CPR format
+    pub lat_cpr: u32,            // 17 bit CPR encoded latitude
+    pub lon_cpr: u32,            // 17 bit CPR encoded longitude
+}
+
+/// Airborne velocity, type code 19 subtypes 1 to 4.
+///
+/// Subtypes 1 and 2 carry a ground speed and track, subtypes 3 and 4 a heading and airspeed.
+/// Subtypes 2 and 4 are for supersonic aircraft and count speeds in 4 knot steps.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AirborneVelocity {
+    pub subtype: u8,
+    pub intent_change: bool, // the aircraft's intent has changed
+    pub nac_v: u8,           // Navigation Accuracy Category for Velocity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<f32>, // knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<f32>, // degrees clockwise from true north
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<f32>, // degrees clockwise from magnetic or true north, see `OperationalStatus`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airspeed: Option<i32>, // knots
+    pub true_airspeed: bool, // the airspeed is true rather than indicated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_rate: Option<i32>, // feet/minute
+    pub vertical_rate_barometric: bool, // the vertical rate is barometric rather than geometric
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometric_minus_barometric: Option<i32>, // GNSS height less barometric altitude, feet
+}
+
+/// Emergency and Mode A code, type code 28 subtype 1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AircraftStatus {
+    pub emergency: String, // emergency state, such as none or minfuel
+    pub squawk: String,    // Mode A code as 4 octal digits
+}
+
+/// Target state and status, type code 29 subtype 1.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TargetState {
+    pub sil_supplement: bool, // SIL is per sample rather than per hour
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude: Option<i32>, // feet
+    pub selected_altitude_fms: bool, // the selected altitude is from the FMS rather than the MCP/FCU
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baro_setting: Option<f32>, // altimeter setting, hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_heading: Option<f32>, // degrees
+    pub nac_p: u8,                   // Navigation Accuracy Category for Position
+    pub nic_baro: u8,                // Navigation Integrity Category for Barometric Altitude
+    pub sil: u8,                     // Source Integrity Level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nav_modes: Option<Vec<NavModes>>, // engaged modes, when the mode bits are valid
+}
+
+/// Aircraft operational status, type code 31 subtypes 0 (airborne) and 1 (surface).
+///
+/// Version 0 transmitters only fill in the version, and GVA and SDA were added in version 2.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OperationalStatus {
+    pub surface: bool,          // subtype 1, sent on the surface
+    pub version: u8,            // ADS-B version number
+    pub nic_supplement_a: bool, // NIC supplement A
+    pub nac_p: u8,              // Navigation Accuracy Category for Position
+    pub sil: u8,                // Source Integrity Level
+    pub sil_supplement: bool,   // SIL is per sample rather than per hour
+    pub heading_true: bool,     // HRD, headings are true rather than magnetic
+    pub tcas_operational: bool, // ACAS is operational, airborne only
+    pub ident_active: bool,     // the IDENT switch is active
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_baro: Option<u8>, // Navigation Integrity Category for Barometric Altitude, airborne only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gva: Option<u8>, // Geometric Vertical Accuracy, airborne version 2 only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sda: Option<u8>, // System Design Assurance, version 2 only
+}
+
+impl ExtendedSquitter {
+    /// Decodes a 7 byte ME field. Bits are numbered from 1, as in DO-260B.
+    pub fn decode(me: &[u8]) -> ExtendedSquitter {
+        if me.len() != ME_LENGTH {
+            return ExtendedSquitter::Unsupported(me.to_vec());
+        }
+        let type_code: u8 = bits(me, 1, 5) as u8;
+        let subtype: u8 = bits(me, 6, 8) as u8;
+        match (type_code, subtype) {
+            (1..=4, _) => ExtendedSquitter::Identification(decode_identification(me, type_code)),
+            (5..=8, _) => ExtendedSquitter::SurfacePosition(decode_surface_position(me)),
+            (9..=18 | 20..=22, _) => {
+                ExtendedSquitter::AirbornePosition(decode_airborne_position(me, type_code))
+            }
+            (19, 1..=4) => ExtendedSquitter::AirborneVelocity(decode_velocity(me, subtype)),
+            (28, 1) => ExtendedSquitter::AircraftStatus(AircraftStatus {
+                emergency: EMERGENCY_STATES[bits(me, 9, 11) as usize].to_string(),
+                squawk: decode_id13(bits(me, 12, 24)),
+            }),
+            (29, _) if bits(me, 6, 7) == 1 => {
+                ExtendedSquitter::TargetState(decode_target_state(me))
+            }
+            (31, 0..=1) => ExtendedSquitter::OperationalStatus(decode_operational_status(me)),
+            _ => ExtendedSquitter::Unsupported(me.to_vec()),
+        }
+    }
+}
+
+fn decode_identification(me: &[u8], type_code: u8) -> Identification {
+    let set: char = match type_code {
+        4 => 'A',
+        3 => 'B',
+        2 => 'C',
+        _ => 'D',
+    };
+    Identification {
+        category: format!("{}{}", set, bits(me, 6, 8)),
+        callsign: decode_callsign(&me[1..]),
+    }
+}
+
+fn decode_surface_position(me: &[u8]) -> SurfacePosition {
+    SurfacePosition {
+        ground_speed: decode_movement(bits(me, 6, 12)),
+        track: match bit(me, 13) {
+            true => Some(bits(me, 14, 20) as f32 * 360.0 / 128.0),
+            false => None,
+        },
+        utc_synchronised: bit(me, 21),
+        odd: bit(me, 22),
+        lat_cpr: bits(me, 23, 39),
+        lon_cpr: bits(me, 40, 56),
+    }
+}
+
+fn decode_airborne_position(me: &[u8], type_code: u8) -> AirbornePosition {
+    AirbornePosition {
+        surveillance_status: bits(me, 6, 7) as u8,
+        nic_supplement_b: bit(me, 8),
+        altitude: decode_ac12(bits(me, 9, 20)),
+        gnss_height: type_code >= 20,
+        utc_synchronised: bit(me, 21),
+        odd: bit(me, 22),
+        lat_cpr: bits(me, 23, 39),
+        lon_cpr: bits(me, 40, 56),
+    }
+}
+
+fn decode_velocity(me: &[u8], subtype: u8) -> AirborneVelocity {
+    let scale: i32 = match subtype {
+        2 | 4 => 4,
+        _ => 1,
+    };
+    let mut velocity: AirborneVelocity = AirborneVelocity {
+        subtype,
+        intent_change: bit(me, 9),
+        nac_v: bits(me, 11, 13) as u8,
+        ground_speed: None,
+        track: None,
+        heading: None,
+        airspeed: None,
+        true_airspeed: false,
+        vertical_rate: signed_field(bit(me, 37), bits(me, 38, 46), 64),
+        vertical_rate_barometric: bit(me, 36),
+        geometric_minus_barometric: signed_field(bit(me, 49), bits(me, 50, 56), 25),
+    };
+    match subtype {
+        1 | 2 => {
+            let east_west: Option<i32> = signed_field(bit(me, 14), bits(me, 15, 24), scale);
+            let north_south: Option<i32> = signed_field(bit(me, 25), bits(me, 26, 35), scale);
+            if let (Some(east_west), Some(north_south)) = (east_west, north_south) {
+                // The sign bits are set for west and south, so these are the east and north components.
+                let east: f32 = east_west as f32;
+                let north: f32 = north_south as f32;
+                velocity.ground_speed = Some(east.hypot(north));
+                velocity.track = Some(east.atan2(north).to_degrees().rem_euclid(360.0));
+            }
+        }
+        _ => {
+            if bit(me, 14) {
+                velocity.heading = Some(bits(me, 15, 24) as f32 * 360.0 / 1024.0);
+            }
+            velocity.true_airspeed = bit(me, 25);
+            velocity.airspeed = match bits(me, 26, 35) as i32 {
+                0 => None,
+                airspeed => Some((airspeed - 1) * scale),
+            };
+        }
+    }
+    velocity
+}
+
+fn decode_target_state(me: &[u8]) -> TargetState {
+    let mut nav_modes: Vec<NavModes> = Vec::new();
+    for (bit_number, mode) in [
+        (48, NavModes::AutoPilot),
+        (49, NavModes::VNAV),
+        (50, NavModes::AltHold),
+        (52, NavModes::Approach),
+        (53, NavModes::TCAS),
+        (54, NavModes::LNAV),
+    ] {
+        if bit(me, bit_number) {
+            nav_modes.push(mode);
+        }
+    }
+    TargetState {
+        sil_supplement: bit(me, 8),
+        selected_altitude: match bits(me, 10, 20) as i32 {
+            0 => None,
+            altitude => Some((altitude - 1) * 32),
+        },
+        selected_altitude_fms: bit(me, 9),
+        baro_setting: match bits(me, 21, 29) {
+            0 => None,
+            setting => Some(800.0 + (setting - 1) as f32 * 0.8),
+        },
+        selected_heading: match bit(me, 30) {
+            true => Some(bits(me, 31, 39) as f32 * 180.0 / 256.0),
+            false => None,
+        },
+        nac_p: bits(me, 40, 43) as u8,
+        nic_baro: bits(me, 44, 44) as u8,
+        sil: bits(me, 45, 46) as u8,
+        nav_modes: match bit(me, 47) {
+            true => Some(nav_modes),
+            false => None,
+        },
+    }
+}
+
+fn decode_operational_status(me: &[u8]) -> OperationalStatus {
+    let surface: bool = bits(me, 6, 8) == 1;
+    let version: u8 = bits(me, 41, 43) as u8;
+    OperationalStatus {
+        surface,
+        version,
+        nic_supplement_a: bit(me, 44),
+        nac_p: bits(me, 45, 48) as u8,
+        sil: bits(me, 51, 52) as u8,
+        sil_supplement: bit(me, 55),
+        heading_true: bit(me, 54),
+        tcas_operational: !surface && bit(me, 11),
+        ident_active: bit(me, 28),
+        nic_baro: match surface {
+            true => None,
+            false => Some(bits(me, 53, 53) as u8),
+        },
+        gva: match (surface, version) {
+            (false, 2..) => Some(bits(me, 49, 50) as u8),
+            _ => None,
+        },
+        sda: match version {
+            2.. => Some(bits(me, 31, 32) as u8),
+            _ => None,
+        },
+    }
+}
+
+/// Decodes a field that counts in `step`s from 1, with 0 meaning no data, and a sign bit set for negative values.
+fn signed_field(negative: bool, value: u32, step: i32) -> Option<i32> {
+    match value as i32 {
+        0 => None,
+        value => match negative {
+            true => Some(-(value - 1) * step),
+            false => Some((value - 1) * step),
+        },
+    }
+}
+
+/// Decodes the movement field of a surface position to the lower bound of its ground speed band, knots.
+fn decode_movement(movement: u32) -> Option<f32> {
+    let movement: f32 = movement as f32;
+    match movement as u32 {
+        1 => Some(0.0),
+        2..=8 => Some((movement - 1.0) * 0.125),
+        9..=12 => Some(1.0 + (movement - 9.0) * 0.25),
+        13..=38 => Some(2.0 + (movement - 13.0) * 0.5),
+        39..=93 => Some(15.0 + (movement - 39.0)),
+        94..=108 => Some(70.0 + (movement - 94.0) * 2.0),
+        109..=123 => Some(100.0 + (movement - 109.0) * 5.0),
+        124 => Some(175.0),
+        _ => None,
+    }
+}
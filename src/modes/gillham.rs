@@ -0,0 +1,100 @@
+/// Feet in a metre, for altitudes reported with the M bit set.
+const FEET_PER_METRE: f64 = 3.280_84;
+
+/// Lowest altitude Gillham code can report, in hundreds of feet.
+const GILLHAM_MINIMUM: i32 = -12;
+
+/// Decodes a 13 bit altitude code, as carried by DF0, DF4, DF16 and DF20, to feet.
+///
+/// The bits are C1 A1 C2 A2 C4 A4 M B1 Q B2 D2 B4 D4.
+/// With the M bit set the other 12 bits count metres. Otherwise, with the Q bit set the other 11 bits count 25 foot steps from -1000 feet,
+/// and with it clear the bits are Gillham grey code in 100 foot steps.
+/// Returns `None` for a zero field, meaning no altitude, and for invalid Gillham codes.
+pub fn decode_ac13(ac13: u32) -> Option<i32> {
+    if ac13 == 0 {
+        return None;
+    }
+    let m_bit: bool = ac13 & 0x0040 != 0;
+    let q_bit: bool = ac13 & 0x0010 != 0;
+    match (m_bit, q_bit) {
+        (true, _) => {
+            let metres: u32 = (ac13 & 0x1f80) >> 1 | ac13 & 0x003f;
+            Some((metres as f64 * FEET_PER_METRE).round() as i32)
+        }
+        (false, true) => {
+            let steps: u32 = (ac13 & 0x1f80) >> 2 | (ac13 & 0x0020) >> 1 | ac13 & 0x000f;
+            Some(steps as i32 * 25 - 1000)
+        }
+        (false, false) => gillham_to_hundreds(id13_to_mode_a(ac13)).map(|hundreds| hundreds * 100),
+    }
+}
+
+/// Decodes the 12 bit altitude code of an extended squitter airborne position to feet.
+///
+/// This is the 13 bit code without its M bit, as extended squitter altitudes are always in feet.
+pub fn decode_ac12(ac12: u32) -> Option<i32> {
+    decode_ac13((ac12 & 0x0fc0) << 1 | ac12 & 0x003f)
+}
+
+/// Decodes a 13 bit identity field, as carried by DF5, DF21 and extended squitter aircraft status, to a squawk.
+///
+/// The bits are C1 A1 C2 A2 C4 A4 X B1 D1 B2 D2 B4 D4.
+pub fn decode_id13(id13: u32) -> String {
+    format!("{:04x}", id13_to_mode_a(id13))
+}
+
+/// Rearranges a 13 bit identity or altitude field into Mode A digits, one per hexadecimal digit as in 0xABCD.
+fn id13_to_mode_a(id13: u32) -> u32 {
+    let bit_of = |position: u32| (id13 >> position) & 1;
+    let a: u32 = bit_of(11) | bit_of(9) << 1 | bit_of(7) << 2;
+    let b: u32 = bit_of(5) | bit_of(3) << 1 | bit_of(1) << 2;
+    let c: u32 = bit_of(12) | bit_of(10) << 1 | bit_of(8) << 2;
+    let d: u32 = bit_of(4) | bit_of(2) << 1 | bit_of(0) << 2;
+    a << 12 | b << 8 | c << 4 | d
+}
+
+/// Converts Mode A digits holding a Gillham code to the altitude in hundreds of feet.
+///
+/// The D4 D2 A4 A2 A1 B4 B2 B1 bits are a Gray code of 500 foot steps, and the C1 C2 C4 bits a reflected code of the 100 foot step within them.
+fn gillham_to_hundreds(mode_a: u32) -> Option<i32> {
+    // D1 is never used for altitude, and at least one of the C bits is always set.
+    if mode_a & 0xffff_8889 != 0 || mode_a & 0x0070 == 0 {
+        return None;
+    }
+    let mut one_hundreds: i32 = 0;
+    for (mask, gray) in [(0x0010, 0x7), (0x0020, 0x3), (0x0040, 0x1)] {
+        if mode_a & mask != 0 {
+            one_hundreds ^= gray;
+        }
+    }
+    // The C bits count 1 to 5, with 7 standing in for 5.
+    if one_hundreds & 5 == 5 {
+        one_hundreds ^= 2;
+    }
+    if one_hundreds > 5 {
+        return None;
+    }
+    let mut five_hundreds: i32 = 0;
+    for (mask, gray) in [
+        (0x0002, 0x0ff), // D2
+        (0x0004, 0x07f), // D4
+        (0x1000, 0x03f), // A1
+        (0x2000, 0x01f), // A2
+        (0x4000, 0x00f), // A4
+        (0x0100, 0x007), // B1
+        (0x0200, 0x003), // B2
+        (0x0400, 0x001), // B4
+    ] {
+        if mode_a & mask != 0 {
+            five_hundreds ^= gray;
+        }
+    }
+    // The 100 foot steps count down in odd 500 foot steps.
+    if five_hundreds & 1 != 0 {
+        one_hundreds = 6 - one_hundreds;
+    }
+    match five_hundreds * 5 + one_hundreds - 13 {
+        hundreds if hundreds < GILLHAM_MINIMUM => None,
+        hundreds => Some(hundreds),
+    }
+}
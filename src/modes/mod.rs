@@ -0,0 +1,405 @@
+pub mod comm_b;
+pub mod crc;
+pub mod extended_squitter;
+pub mod gillham;
+
+use crate::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use crate::beast::BeastMessage;
+use crate::modes::comm_b::{CommB, MB_LENGTH};
+use crate::modes::crc::{check_frame, modes_syndrome, ErrorCorrection, FrameQuality};
+use crate::modes::extended_squitter::{ExtendedSquitter, ME_LENGTH};
+use crate::modes::gillham::{decode_ac13, decode_id13};
+use crate::{message_error, MessageResult};
+use serde::{Deserialize, Serialize};
+
+/// Length in bytes of a short, 56 bit, Mode-S frame.
+pub const MODES_SHORT_LENGTH: usize = 7;
+
+/// Length in bytes of a long, 112 bit, Mode-S frame.
+pub const MODES_LONG_LENGTH: usize = 14;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single Mode-S frame of 7 or 14 bytes, and will not consume the source.
+pub trait NewModeSMessage {
+    fn to_modes(&self) -> MessageResult<ModeSMessage>;
+}
+
+/// Implementing `.to_modes()` for the type `BeastMessage`, decoding its `data`.
+///
+/// This does not consume the `BeastMessage`.
+impl NewModeSMessage for BeastMessage {
+    fn to_modes(&self) -> MessageResult<ModeSMessage> {
+        self.data.to_modes()
+    }
+}
+
+/// Implementing `.to_modes()` for the type `Vec<u8>`.
+///
+/// This does not consume the `Vec<u8>`.
+impl NewModeSMessage for Vec<u8> {
+    fn to_modes(&self) -> MessageResult<ModeSMessage> {
+        self.as_slice().to_modes()
+    }
+}
+
+/// Supporting `.to_modes()` for the type `[u8]`.
+///
+/// The CRC is checked with single bit error correction, as readsb does by default. See `ModeSMessage::from_frame` for the other options.
+/// This does not consume the `[u8]`.
+impl NewModeSMessage for [u8] {
+    fn to_modes(&self) -> MessageResult<ModeSMessage> {
+        ModeSMessage::from_frame(self, ErrorCorrection::default())
+    }
+}
+
+/// A decoded Mode-S frame.
+///
+/// Positions are kept as raw CPR values, so `to_adsb_json` leaves `lat`, `lon` and `seen_pos` as `None`.
+/// Use a `CprDecoder` to resolve them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModeSMessage {
+    pub downlink_format: u8, // DF, 24 for all frames starting with binary 11
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability: Option<u8>, // CA of DF11 and DF17
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub control_field: Option<u8>, // CF of DF18
+    pub address: u32,        // 24 bit ICAO, or other, address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_status: Option<u8>, // FS of DF4, DF5, DF20 and DF21
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_status: Option<u8>, // VS of DF0 and DF16, 1 on the ground
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>, // AC field of DF0, DF4, DF16 and DF20, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub squawk: Option<String>, // ID field of DF5 and DF21
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_code: Option<u8>, // ME type code of DF17 and DF18
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extended_squitter: Option<ExtendedSquitter>, // decoded ME field of DF17 and DF18
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comm_b: Option<CommB>, // decoded MB field of DF20 and DF21
+    pub quality: FrameQuality, // result of the CRC check
+}
+
+impl ModeSMessage {
+    /// Decodes a single Mode-S frame of 7 or 14 bytes, checking its CRC and correcting up to the bits allowed by `correction`.
+    ///
+    /// All-call replies (DF11) and extended squitters (DF17 and DF18) that fail the CRC return an error.
+    /// Surveillance and Comm-B replies (DF0, 4, 5, 16, 20 and 21) have their address recovered from the address/parity field.
+    /// Other downlink formats return an error.
+    pub fn from_frame(frame: &[u8], correction: ErrorCorrection) -> MessageResult<ModeSMessage> {
+        let downlink_format: u8 = match frame.first() {
+            None => return Err(message_error("Empty Mode-S frame")),
+            Some(first) => (first >> 3).min(24),
+        };
+        let expected_length: usize = match downlink_format {
+            16.. => MODES_LONG_LENGTH,
+            _ => MODES_SHORT_LENGTH,
+        };
+        if frame.len() != expected_length {
+            return Err(message_error(format!(
+                "Mode-S DF{} frame must be {} bytes, found {}",
+                downlink_format,
+                expected_length,
+                frame.len()
+            )));
+        }
+        let message: ModeSMessage = match downlink_format {
+            0 | 16 => ModeSMessage {
+                downlink_format,
+                vertical_status: Some(bits(frame, 6, 6) as u8),
+                address: modes_syndrome(frame),
+                altitude: decode_ac13(bits(frame, 20, 32)),
+                quality: FrameQuality::AddressParity,
+                ..Default::default()
+            },
+            4 | 5 | 20 | 21 => ModeSMessage {
+                downlink_format,
+                flight_status: Some(bits(frame, 6, 8) as u8),
+                address: modes_syndrome(frame),
+                altitude: match downlink_format {
+                    4 | 20 => decode_ac13(bits(frame, 20, 32)),
+                    _ => None,
+                },
+                squawk: match downlink_format {
+                    5 | 21 => Some(decode_id13(bits(frame, 20, 32))),
+                    _ => None,
+                },
+                comm_b: match downlink_format {
+                    20 | 21 => CommB::decode(&frame[4..4 + MB_LENGTH]),
+                    _ => None,
+                },
+                quality: FrameQuality::AddressParity,
+                ..Default::default()
+            },
+            11 | 17 | 18 => {
+                let (frame, quality) = match check_frame(frame, correction) {
+                    None => {
+                        return Err(message_error(format!(
+                            "Mode-S DF{} frame failed its CRC with syndrome {:06x}",
+                            downlink_format,
+                            modes_syndrome(frame)
+                        )))
+                    }
+                    Some(checked) => checked,
+                };
+                let control_field: u8 = frame[0] & 0x07;
+                let has_me: bool = match downlink_format {
+                    17 => true,
+                    // Coarse TIS-B positions, TIS-B management and reserved fields do not use the ADS-B ME formats.
+                    18 => !matches!(control_field, 3 | 4 | 7),
+                    _ => false,
+                };
+                ModeSMessage {
+                    downlink_format,
+                    capability: match downlink_format {
+                        18 => None,
+                        _ => Some(control_field),
+                    },
+                    control_field: match downlink_format {
+                        18 => Some(control_field),
+                        _ => None,
+                    },
+                    address: bits(&frame, 9, 32),
+                    type_code: match downlink_format {
+                        17 | 18 => Some(bits(&frame, 33, 37) as u8),
+                        _ => None,
+                    },
+                    extended_squitter: match has_me {
+                        true => Some(ExtendedSquitter::decode(&frame[4..4 + ME_LENGTH])),
+                        false => None,
+                    },
+                    quality,
+                    ..Default::default()
+                }
+            }
+            _ => {
+                return Err(message_error(format!(
+                    "Unsupported Mode-S downlink format {}",
+                    downlink_format
+                )))
+            }
+        };
+        trace!("Decoded Mode-S frame {:?}", &message);
+        Ok(message)
+    }
+
+    /// Returns whether the aircraft reports being on the ground, from the flight status, vertical status or capability.
+    pub fn on_ground(&self) -> Option<bool> {
+        match (self.flight_status, self.vertical_status, self.capability) {
+            (Some(1 | 3), _, _) | (_, Some(1), _) | (_, _, Some(4)) => Some(true),
+            (Some(0 | 2), _, _) | (_, Some(0), _) | (_, _, Some(5)) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the address is not an ICAO address, such as a TIS-B track file number or an anonymous address.
+    pub fn is_non_icao(&self) -> bool {
+        matches!(self.control_field, Some(1) | Some(3) | Some(5))
+    }
+
+    /// Returns the address as readsb writes `hex`, lowercase with a `~` prefix for non-ICAO addresses.
+    pub fn hex(&self) -> String {
+        match self.is_non_icao() {
+            true => format!("~{:06x}", self.address),
+            false => format!("{:06x}", self.address),
+        }
+    }
+
+    /// Returns the source of the message as readsb writes `type`, such as `adsb_icao` or `tisb_other`.
+    pub fn address_type(&self) -> &'static str {
+        match (self.downlink_format, self.control_field) {
+            (17, _) => "adsb_icao",
+            (18, Some(0)) => "adsb_icao_nt",
+            (18, Some(1)) => "adsb_other",
+            (18, Some(2)) => "tisb_icao",
+            (18, Some(3)) => "tisb_trackfile",
+            (18, Some(5)) => "tisb_other",
+            (18, Some(6)) => "adsr_icao",
+            _ => "mode_s",
+        }
+    }
+
+    /// Converts `ModeSMessage` to an `ADSBJsonMessage` holding the fields carried by this one frame.
+    ///
+    /// `now` is the Unix timestamp the frame was received. Positions are not resolved, so `seen_pos` is `None`.
+    pub fn to_adsb_json(&self, now: f64) -> ADSBJsonMessage {
+        let mut message: ADSBJsonMessage = ADSBJsonMessage {
+            now,
+            hex: self.hex(),
+            adsb_type: self.address_type().to_string(),
+            messages: 1,
+            ..Default::default()
+        };
+        let type_code: u8 = self.type_code.unwrap_or_default();
+        match &self.extended_squitter {
+            None | Some(ExtendedSquitter::Unsupported(_)) => {}
+            Some(ExtendedSquitter::Identification(identification)) => {
+                message.flight = Some(identification.callsign.clone());
+                message.category = Some(identification.category.clone());
+            }
+            Some(ExtendedSquitter::SurfacePosition(position)) => {
+                message.alt_baro = Some(Altitude::Ground("ground".to_string()));
+                message.gs = position.ground_speed;
+                message.track = position.track;
+                let (nic, rc) = position_integrity(type_code, false);
+                (message.nic, message.rc) = (Some(nic), Some(rc));
+            }
+            Some(ExtendedSquitter::AirbornePosition(position)) => {
+                match position.gnss_height {
+                    true => message.alt_geom = position.altitude,
+                    false => {
+                        if let Some(altitude) = position.altitude {
+                            message.alt_baro = Some(Altitude::I32(altitude));
+                        }
+                    }
+                }
+                message.alert = Some(matches!(position.surveillance_status, 1 | 2) as i8);
+                message.spi = Some((position.surveillance_status == 3) as i8);
+                let (nic, rc) = position_integrity(type_code, position.nic_supplement_b);
+                (message.nic, message.rc) = (Some(nic), Some(rc));
+            }
+            Some(ExtendedSquitter::AirborneVelocity(velocity)) => {
+                message.gs = velocity.ground_speed;
+                message.track = velocity.track;
+                message.mag_heading = velocity.heading;
+                match velocity.true_airspeed {
+                    true => message.tas = velocity.airspeed,
+                    false => message.ias = velocity.airspeed,
+                }
+                match velocity.vertical_rate_barometric {
+                    true => message.baro_rate = velocity.vertical_rate,
+                    false => message.geom_rate = velocity.vertical_rate,
+                }
+                message.nac_v = Some(velocity.nac_v as i8);
+            }
+            Some(ExtendedSquitter::AircraftStatus(status)) => {
+                message.emergency = Some(status.emergency.clone());
+                message.squawk = Some(status.squawk.clone());
+            }
+            Some(ExtendedSquitter::TargetState(state)) => {
+                match state.selected_altitude_fms {
+                    true => message.nav_altitude_fms = state.selected_altitude,
+                    false => message.nav_altitude_mcp = state.selected_altitude,
+                }
+                message.nav_qnh = state.baro_setting;
+                message.nav_heading = state.selected_heading;
+                message.nav_modes = state.nav_modes.clone();
+                message.nac_p = Some(state.nac_p as i8);
+                message.nic_baro = Some(state.nic_baro as i8);
+                message.sil = Some(state.sil as i8);
+                message.sil_type = sil_type(state.sil_supplement);
+            }
+            Some(ExtendedSquitter::OperationalStatus(status)) => {
+                message.version = Some(status.version as i32);
+                if status.version > 0 {
+                    message.nac_p = Some(status.nac_p as i8);
+                    message.sil = Some(status.sil as i8);
+                    message.sil_type = sil_type(status.sil_supplement);
+                    message.nic_baro = status.nic_baro.map(|nic_baro| nic_baro as i8);
+                    message.gva = status.gva.map(|gva| gva as i8);
+                    message.sda = status.sda.map(|sda| sda as i8);
+                }
+            }
+        }
+        match &self.comm_b {
+            None | Some(CommB::Unknown(_)) => {}
+            Some(CommB::DataLinkCapability(_))
+            | Some(CommB::GicbCapability(_))
+            | Some(CommB::AcasResolutionAdvisory(_)) => {}
+            Some(CommB::Identification(callsign)) => message.flight = Some(callsign.clone()),
+            Some(CommB::SelectedVerticalIntention(intention)) => {
+                message.nav_altitude_mcp = intention.mcp_altitude;
+                message.nav_altitude_fms = intention.fms_altitude;
+                message.nav_qnh = intention.baro_setting;
+                if let (Some(vnav), Some(altitude_hold), Some(approach)) =
+                    (intention.vnav, intention.altitude_hold, intention.approach)
+                {
+                    message.nav_modes = Some(
+                        [
+                            (vnav, NavModes::VNAV),
+                            (altitude_hold, NavModes::AltHold),
+                            (approach, NavModes::Approach),
+                        ]
+                        .into_iter()
+                        .filter(|(engaged, _)| *engaged)
+                        .map(|(_, mode)| mode)
+                        .collect(),
+                    );
+                }
+            }
+            Some(CommB::Meteorological(meteorological)) => {
+                message.ws = Some(meteorological.wind_speed as i32);
+                message.wd = Some(meteorological.wind_direction.round() as i32);
+                message.oat = Some(meteorological.temperature.round() as i32);
+            }
+            Some(CommB::TrackAndTurn(track_and_turn)) => {
+                message.roll = track_and_turn.roll;
+                message.track = track_and_turn.track;
+                message.gs = track_and_turn.ground_speed.map(|speed| speed as f32);
+                message.track_rate = track_and_turn.track_rate;
+                message.tas = track_and_turn.true_airspeed.map(|speed| speed as i32);
+            }
+            Some(CommB::HeadingAndSpeed(heading_and_speed)) => {
+                message.mag_heading = heading_and_speed.magnetic_heading;
+                message.ias = heading_and_speed
+                    .indicated_airspeed
+                    .map(|speed| speed as i32);
+                message.mach = heading_and_speed.mach;
+                message.baro_rate = heading_and_speed.baro_rate;
+                message.geom_rate = heading_and_speed.inertial_rate;
+            }
+        }
+        if let Some(altitude) = self.altitude {
+            message.alt_baro = Some(Altitude::I32(altitude));
+        }
+        if self.on_ground() == Some(true) {
+            message.alt_baro = Some(Altitude::Ground("ground".to_string()));
+        }
+        if let Some(squawk) = &self.squawk {
+            message.squawk = Some(squawk.clone());
+        }
+        if let Some(flight_status) = self.flight_status {
+            message.alert = Some(matches!(flight_status, 2..=4) as i8);
+            message.spi = Some(matches!(flight_status, 4 | 5) as i8);
+        }
+        message
+    }
+}
+/// Returns the Navigation Integrity Category and radius of containment in metres of a position type code.
+///
+/// NIC supplement A comes from the operational status, so the categories that need it are reported at their lower value.
+fn position_integrity(type_code: u8, nic_supplement_b: bool) -> (i32, i32) {
+    match (type_code, nic_supplement_b) {
+        (5 | 9 | 20, _) => (11, 8),
+        (6 | 10 | 21, _) => (10, 25),
+        (7 | 11, _) => (8, 186),
+        (12, _) => (7, 371),
+        (13, true) => (6, 556),
+        (13, false) => (6, 1112),
+        (14, _) => (5, 1852),
+        (15, _) => (4, 3704),
+        (16, true) => (3, 7408),
+        (16, false) => (2, 14816),
+        (17, _) => (1, 37040),
+        _ => (0, 0),
+    }
+}
+
+fn sil_type(sil_supplement: bool) -> SilType {
+    match sil_supplement {
+        true => SilType::PerSample,
+        false => SilType::PerHour,
+    }
+}
+
+/// Reads bits `first` to `last` of `data` as an unsigned number, numbering bits from 1 at the most significant bit.
+pub(crate) fn bits(data: &[u8], first: usize, last: usize) -> u32 {
+    (first..=last).fold(0, |value, number| value << 1 | bit(data, number) as u32)
+}
+
+/// Reads bit `number` of `data`, numbering bits from 1 at the most significant bit.
+pub(crate) fn bit(data: &[u8], number: usize) -> bool {
+    data[(number - 1) / 8] & (0x80 >> ((number - 1) % 8)) != 0
+}
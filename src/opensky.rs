@@ -0,0 +1,310 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude};
+use crate::{METRES_PER_FOOT, METRES_PER_SECOND_PER_FOOT_PER_MINUTE, METRES_PER_SECOND_PER_KNOT};
+use serde::{Deserialize, Serialize};
+
+/// Position source of a state vector, as numbered by OpenSky.
+pub const POSITION_SOURCE_ADSB: u8 = 0;
+pub const POSITION_SOURCE_ASTERIX: u8 = 1;
+pub const POSITION_SOURCE_MLAT: u8 = 2;
+pub const POSITION_SOURCE_FLARM: u8 = 3;
+
+/// Blocks of 24 bit addresses allocated to each state, from Annex 10 Volume III.
+const ICAO_ADDRESS_BLOCKS: &[(u32, u32, &str)] = &[
+    (0x004000, 0x0043ff, "Zimbabwe"),
+    (0x006000, 0x006fff, "Mozambique"),
+    (0x008000, 0x00ffff, "South Africa"),
+    (0x010000, 0x017fff, "Egypt"),
+    (0x018000, 0x01ffff, "Libya"),
+    (0x020000, 0x027fff, "Morocco"),
+    (0x028000, 0x02ffff, "Tunisia"),
+    (0x030000, 0x0303ff, "Botswana"),
+    (0x032000, 0x032fff, "Burundi"),
+    (0x034000, 0x034fff, "Cameroon"),
+    (0x035000, 0x0353ff, "Comoros"),
+    (0x036000, 0x036fff, "Congo"),
+    (0x038000, 0x038fff, "Cote d'Ivoire"),
+    (0x03e000, 0x03efff, "Gabon"),
+    (0x040000, 0x040fff, "Ethiopia"),
+    (0x042000, 0x042fff, "Equatorial Guinea"),
+    (0x044000, 0x044fff, "Ghana"),
+    (0x046000, 0x046fff, "Guinea"),
+    (0x048000, 0x0483ff, "Guinea-Bissau"),
+    (0x04a000, 0x04a3ff, "Lesotho"),
+    (0x04c000, 0x04cfff, "Kenya"),
+    (0x050000, 0x050fff, "Liberia"),
+    (0x054000, 0x054fff, "Madagascar"),
+    (0x058000, 0x058fff, "Malawi"),
+    (0x05a000, 0x05a3ff, "Maldives"),
+    (0x05c000, 0x05cfff, "Mali"),
+    (0x05e000, 0x05e3ff, "Mauritania"),
+    (0x060000, 0x0603ff, "Mauritius"),
+    (0x062000, 0x062fff, "Niger"),
+    (0x064000, 0x064fff, "Nigeria"),
+    (0x068000, 0x068fff, "Uganda"),
+    (0x06a000, 0x06a3ff, "Qatar"),
+    (0x06c000, 0x06cfff, "Central African Republic"),
+    (0x06e000, 0x06efff, "Rwanda"),
+    (0x070000, 0x070fff, "Senegal"),
+    (0x074000, 0x0743ff, "Seychelles"),
+    (0x076000, 0x0763ff, "Sierra Leone"),
+    (0x078000, 0x078fff, "Somalia"),
+    (0x07a000, 0x07a3ff, "Eswatini"),
+    (0x07c000, 0x07cfff, "Sudan"),
+    (0x080000, 0x080fff, "Tanzania"),
+    (0x084000, 0x084fff, "Chad"),
+    (0x088000, 0x088fff, "Togo"),
+    (0x08a000, 0x08afff, "Zambia"),
+    (0x08c000, 0x08cfff, "DR Congo"),
+    (0x090000, 0x090fff, "Angola"),
+    (0x094000, 0x0943ff, "Benin"),
+    (0x096000, 0x0963ff, "Cape Verde"),
+    (0x098000, 0x0983ff, "Djibouti"),
+    (0x09a000, 0x09afff, "Gambia"),
+    (0x09c000, 0x09cfff, "Burkina Faso"),
+    (0x09e000, 0x09e3ff, "Sao Tome and Principe"),
+    (0x0a0000, 0x0a7fff, "Algeria"),
+    (0x0a8000, 0x0a8fff, "Bahamas"),
+    (0x0aa000, 0x0aa3ff, "Barbados"),
+    (0x0ab000, 0x0ab3ff, "Belize"),
+    (0x0ac000, 0x0acfff, "Colombia"),
+    (0x0ae000, 0x0aefff, "Costa Rica"),
+    (0x0b0000, 0x0b0fff, "Cuba"),
+    (0x0b2000, 0x0b2fff, "El Salvador"),
+    (0x0b4000, 0x0b4fff, "Guatemala"),
+    (0x0b6000, 0x0b6fff, "Guyana"),
+    (0x0b8000, 0x0b8fff, "Haiti"),
+    (0x0ba000, 0x0bafff, "Honduras"),
+    (0x0bc000, 0x0bc3ff, "Saint Vincent and the Grenadines"),
+    (0x0be000, 0x0befff, "Jamaica"),
+    (0x0c0000, 0x0c0fff, "Nicaragua"),
+    (0x0c2000, 0x0c2fff, "Panama"),
+    (0x0c4000, 0x0c4fff, "Dominican Republic"),
+    (0x0c6000, 0x0c6fff, "Trinidad and Tobago"),
+    (0x0c8000, 0x0c8fff, "Suriname"),
+    (0x0ca000, 0x0ca3ff, "Antigua and Barbuda"),
+    (0x0cc000, 0x0cc3ff, "Grenada"),
+    (0x0d0000, 0x0d7fff, "Mexico"),
+    (0x0d8000, 0x0dffff, "Venezuela"),
+    (0x100000, 0x1fffff, "Russia"),
+    (0x201000, 0x2013ff, "Namibia"),
+    (0x202000, 0x2023ff, "Eritrea"),
+    (0x300000, 0x33ffff, "Italy"),
+    (0x340000, 0x37ffff, "Spain"),
+    (0x380000, 0x3bffff, "France"),
+    (0x3c0000, 0x3fffff, "Germany"),
+    (0x400000, 0x43ffff, "United Kingdom"),
+    (0x440000, 0x447fff, "Austria"),
+    (0x448000, 0x44ffff, "Belgium"),
+    (0x450000, 0x457fff, "Bulgaria"),
+    (0x458000, 0x45ffff, "Denmark"),
+    (0x460000, 0x467fff, "Finland"),
+    (0x468000, 0x46ffff, "Greece"),
+    (0x470000, 0x477fff, "Hungary"),
+    (0x478000, 0x47ffff, "Norway"),
+    (0x480000, 0x487fff, "Netherlands"),
+    (0x488000, 0x48ffff, "Poland"),
+    (0x490000, 0x497fff, "Portugal"),
+    (0x498000, 0x49ffff, "Czechia"),
+    (0x4a0000, 0x4a7fff, "Romania"),
+    (0x4a8000, 0x4affff, "Sweden"),
+    (0x4b0000, 0x4b7fff, "Switzerland"),
+    (0x4b8000, 0x4bffff, "Turkey"),
+    (0x4c0000, 0x4c7fff, "Serbia"),
+    (0x4c8000, 0x4c83ff, "Cyprus"),
+    (0x4ca000, 0x4cafff, "Ireland"),
+    (0x4cc000, 0x4ccfff, "Iceland"),
+    (0x4d0000, 0x4d03ff, "Luxembourg"),
+    (0x4d2000, 0x4d2fff, "Malta"),
+    (0x4d4000, 0x4d43ff, "Monaco"),
+    (0x500000, 0x5003ff, "San Marino"),
+    (0x501000, 0x5013ff, "Albania"),
+    (0x501c00, 0x501fff, "Croatia"),
+    (0x502c00, 0x502fff, "Latvia"),
+    (0x503c00, 0x503fff, "Lithuania"),
+    (0x504c00, 0x504fff, "Moldova"),
+    (0x505c00, 0x505fff, "Slovakia"),
+    (0x506c00, 0x506fff, "Slovenia"),
+    (0x507c00, 0x507fff, "Uzbekistan"),
+    (0x508000, 0x50ffff, "Ukraine"),
+    (0x510000, 0x5103ff, "Belarus"),
+    (0x511000, 0x5113ff, "Estonia"),
+    (0x512000, 0x5123ff, "North Macedonia"),
+    (0x513000, 0x5133ff, "Bosnia and Herzegovina"),
+    (0x514000, 0x5143ff, "Georgia"),
+    (0x515000, 0x5153ff, "Tajikistan"),
+    (0x516000, 0x5163ff, "Montenegro"),
+    (0x600000, 0x6003ff, "Armenia"),
+    (0x600800, 0x600bff, "Azerbaijan"),
+    (0x601000, 0x6013ff, "Kyrgyzstan"),
+    (0x601800, 0x601bff, "Turkmenistan"),
+    (0x680000, 0x6803ff, "Bhutan"),
+    (0x681000, 0x6813ff, "Micronesia"),
+    (0x682000, 0x6823ff, "Mongolia"),
+    (0x683000, 0x6833ff, "Kazakhstan"),
+    (0x684000, 0x6843ff, "Palau"),
+    (0x700000, 0x700fff, "Afghanistan"),
+    (0x702000, 0x702fff, "Bangladesh"),
+    (0x704000, 0x704fff, "Myanmar"),
+    (0x706000, 0x706fff, "Kuwait"),
+    (0x708000, 0x708fff, "Laos"),
+    (0x70a000, 0x70afff, "Nepal"),
+    (0x70c000, 0x70c3ff, "Oman"),
+    (0x70e000, 0x70efff, "Cambodia"),
+    (0x710000, 0x717fff, "Saudi Arabia"),
+    (0x718000, 0x71ffff, "Republic of Korea"),
+    (0x720000, 0x727fff, "Democratic People's Republic of Korea"),
+    (0x728000, 0x72ffff, "Iraq"),
+    (0x730000, 0x737fff, "Iran"),
+    (0x738000, 0x73ffff, "Israel"),
+    (0x740000, 0x747fff, "Jordan"),
+    (0x748000, 0x74ffff, "Lebanon"),
+    (0x750000, 0x757fff, "Malaysia"),
+    (0x758000, 0x75ffff, "Philippines"),
+    (0x760000, 0x767fff, "Pakistan"),
+    (0x768000, 0x76ffff, "Singapore"),
+    (0x770000, 0x777fff, "Sri Lanka"),
+    (0x778000, 0x77ffff, "Syria"),
+    (0x780000, 0x7bffff, "China"),
+    (0x7c0000, 0x7fffff, "Australia"),
+    (0x800000, 0x83ffff, "India"),
+    (0x840000, 0x87ffff, "Japan"),
+    (0x880000, 0x887fff, "Thailand"),
+    (0x888000, 0x88ffff, "Viet Nam"),
+    (0x890000, 0x890fff, "Yemen"),
+    (0x894000, 0x894fff, "Bahrain"),
+    (0x895000, 0x8953ff, "Brunei"),
+    (0x896000, 0x896fff, "United Arab Emirates"),
+    (0x897000, 0x8973ff, "Solomon Islands"),
+    (0x898000, 0x898fff, "Papua New Guinea"),
+    (0x899000, 0x8993ff, "Taiwan"),
+    (0x8a0000, 0x8a7fff, "Indonesia"),
+    (0x900000, 0x9003ff, "Marshall Islands"),
+    (0x901000, 0x9013ff, "Cook Islands"),
+    (0x902000, 0x9023ff, "Samoa"),
+    (0xa00000, 0xafffff, "United States"),
+    (0xc00000, 0xc3ffff, "Canada"),
+    (0xc80000, 0xc87fff, "New Zealand"),
+    (0xc88000, 0xc88fff, "Fiji"),
+    (0xc8a000, 0xc8a3ff, "Nauru"),
+    (0xc8c000, 0xc8c3ff, "Saint Lucia"),
+    (0xc8d000, 0xc8d3ff, "Tonga"),
+    (0xc8e000, 0xc8e3ff, "Kiribati"),
+    (0xc90000, 0xc903ff, "Vanuatu"),
+    (0xe00000, 0xe3ffff, "Argentina"),
+    (0xe40000, 0xe7ffff, "Brazil"),
+    (0xe80000, 0xe80fff, "Chile"),
+    (0xe84000, 0xe84fff, "Ecuador"),
+    (0xe88000, 0xe88fff, "Paraguay"),
+    (0xe8c000, 0xe8cfff, "Peru"),
+    (0xe90000, 0xe90fff, "Uruguay"),
+    (0xe94000, 0xe94fff, "Bolivia"),
+    (0xf00000, 0xf07fff, "ICAO (temporary assignments)"),
+    (0xf09000, 0xf093ff, "ICAO (special use)"),
+];
+
+/// Returns the state a 24 bit ICAO address was allocated to, if it falls in an allocated block.
+pub fn icao_country(address: u32) -> Option<&'static str> {
+    ICAO_ADDRESS_BLOCKS
+        .iter()
+        .find(|(start, end, _)| (*start..=*end).contains(&address))
+        .map(|(_, _, country)| *country)
+}
+
+/// An aircraft state in the layout of the OpenSky Network state vectors.
+///
+/// Altitudes are in metres, speeds in metres per second, and times are Unix timestamps in seconds.
+/// The timestamps keep the fractional seconds of the source, where OpenSky itself truncates them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StateVector {
+    pub icao24: String,             // ICAO address, lower case
+    pub callsign: Option<String>,   // callsign, padded to 8 characters
+    pub origin_country: String,     // state the ICAO address was allocated to
+    pub time_position: Option<f64>, // Unix timestamp of the last position update
+    pub last_contact: f64,          // Unix timestamp of the last message received
+    pub longitude: Option<f32>,     // longitude
+    pub latitude: Option<f32>,      // latitude
+    pub baro_altitude: Option<f32>, // barometric altitude, metres
+    pub on_ground: bool,            // the position was from a surface position report
+    pub velocity: Option<f32>,      // ground speed, metres/second
+    pub true_track: Option<f32>,    // track, degrees clockwise from north
+    pub vertical_rate: Option<f32>, // vertical rate, metres/second, negative is descending
+    pub sensors: Option<Vec<i32>>,  // IDs of the receivers, only set for the owner of the receivers
+    pub geo_altitude: Option<f32>,  // geometric altitude, metres
+    pub squawk: Option<String>,     // squawk
+    pub spi: bool,                  // special purpose indicator
+    pub position_source: u8,        // POSITION_SOURCE_* origin of the position
+    pub category: Option<u8>,       // OpenSky aircraft category, 0 is no information
+}
+
+impl From<&ADSBJsonMessage> for StateVector {
+    fn from(message: &ADSBJsonMessage) -> Self {
+        let (baro_altitude, on_ground) = match &message.alt_baro {
+            Some(Altitude::I32(altitude)) => (Some(*altitude as f32 * METRES_PER_FOOT), false),
+            Some(Altitude::Ground(_)) => (None, true),
+            None => (None, false),
+        };
+        let position_source: u8 = match message.is_mlat_position() {
+            true => POSITION_SOURCE_MLAT,
+            false => POSITION_SOURCE_ADSB,
+        };
+        StateVector {
+            icao24: message.hex.to_lowercase(),
+            callsign: message.flight.clone(),
+            origin_country: match message.hex.starts_with('~') {
+                true => None,
+                false => u32::from_str_radix(&message.hex, 16)
+                    .ok()
+                    .and_then(icao_country),
+            }
+            .unwrap_or_default()
+            .to_string(),
+            time_position: message
+                .position()
+                .map(|_| message.now - message.seen_pos.unwrap_or(message.seen)),
+            last_contact: message.now - message.seen,
+            longitude: message.lon,
+            latitude: message.lat,
+            baro_altitude,
+            on_ground,
+            velocity: message.gs.map(|gs| gs * METRES_PER_SECOND_PER_KNOT),
+            true_track: message.track,
+            vertical_rate: message
+                .baro_rate
+                .or(message.geom_rate)
+                .map(|rate| rate as f32 * METRES_PER_SECOND_PER_FOOT_PER_MINUTE),
+            sensors: None,
+            geo_altitude: message
+                .alt_geom
+                .map(|altitude| altitude as f32 * METRES_PER_FOOT),
+            squawk: message.squawk.clone(),
+            spi: message.spi == Some(1),
+            position_source,
+            category: message.category.as_deref().map(opensky_category),
+        }
+    }
+}
+
+/// Converts an emitter category, such as A3, to the OpenSky category number.
+fn opensky_category(category: &str) -> u8 {
+    match category {
+        "A1" => 2,
+        "A2" => 3,
+        "A3" => 4,
+        "A4" => 5,
+        "A5" => 6,
+        "A6" => 7,
+        "A7" => 8,
+        "B1" => 9,
+        "B2" => 10,
+        "B3" => 11,
+        "B4" => 12,
+        "B6" => 14,
+        "B7" => 15,
+        "C1" => 16,
+        "C2" => 17,
+        "C3" => 18,
+        "C4" => 19,
+        "C5" => 20,
+        _ => 1,
+    }
+}
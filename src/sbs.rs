@@ -0,0 +1,402 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude};
+use crate::{message_error, timestamp_to_date_time, MessageResult};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Record types that can start a BaseStation line.
+///
+/// Only `MSG` records carry aircraft data, the others are recognised so they can be rejected with a useful error.
+const SBS_RECORD_TYPES: [&str; 6] = ["MSG", "SEL", "ID", "AIR", "STA", "CLK"];
+
+/// Format of the date columns in a BaseStation line.
+const SBS_DATE_FORMAT: &str = "%Y/%m/%d";
+
+/// Format of the time columns in a BaseStation line.
+const SBS_TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single BaseStation (SBS-1) `MSG` line, as sent on port 30003, and will not consume the source.
+pub trait NewSbsMessage {
+    fn to_sbs(&self) -> MessageResult<SbsMessage>;
+}
+
+/// Implementing `.to_sbs()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewSbsMessage for String {
+    fn to_sbs(&self) -> MessageResult<SbsMessage> {
+        self.as_str().to_sbs()
+    }
+}
+
+/// Supporting `.to_sbs()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewSbsMessage for str {
+    fn to_sbs(&self) -> MessageResult<SbsMessage> {
+        let fields: Vec<&str> = self.trim().split(',').map(str::trim).collect();
+        match fields[0] {
+            "MSG" => {}
+            record_type if SBS_RECORD_TYPES.contains(&record_type) => {
+                return Err(message_error(format!(
+                    "Unsupported SBS record type {}: {}",
+                    record_type, self
+                )))
+            }
+            _ => return Err(message_error(format!("Unrecognised SBS line: {}", self))),
+        }
+        if fields.len() < 10 {
+            return Err(message_error(format!(
+                "SBS line has {} fields, expected at least 10: {}",
+                fields.len(),
+                self
+            )));
+        }
+        let field = |index: usize| -> &str { fields.get(index).copied().unwrap_or_default() };
+        let transmission_type: SbsTransmissionType =
+            match parse_field::<u8>(field(1), "transmission type")?
+                .and_then(SbsTransmissionType::from_number)
+            {
+                None => {
+                    return Err(message_error(format!(
+                        "Invalid SBS transmission type {}: {}",
+                        field(1),
+                        self
+                    )))
+                }
+                Some(transmission_type) => transmission_type,
+            };
+        if field(4).is_empty() {
+            return Err(message_error(format!(
+                "SBS line has no hex ident: {}",
+                self
+            )));
+        }
+        Ok(SbsMessage {
+            transmission_type,
+            session_id: parse_field(field(2), "session id")?,
+            aircraft_id: parse_field(field(3), "aircraft id")?,
+            hex_ident: field(4).to_string(),
+            flight_id: parse_field(field(5), "flight id")?,
+            generated: parse_date_time(field(6), field(7))?,
+            logged: parse_date_time(field(8), field(9))?,
+            callsign: parse_field(field(10), "callsign")?,
+            altitude: parse_field(field(11), "altitude")?,
+            ground_speed: parse_field(field(12), "ground speed")?,
+            track: parse_field(field(13), "track")?,
+            lat: parse_field(field(14), "latitude")?,
+            lon: parse_field(field(15), "longitude")?,
+            vertical_rate: parse_field(field(16), "vertical rate")?,
+            squawk: parse_field(field(17), "squawk")?,
+            alert: parse_flag(field(18), "alert")?,
+            emergency: parse_flag(field(19), "emergency")?,
+            spi: parse_flag(field(20), "spi")?,
+            is_on_ground: parse_flag(field(21), "is on ground")?,
+        })
+    }
+}
+
+/// Returns `true` if `line` starts with one of the BaseStation record types.
+pub(crate) fn is_sbs_line(line: &str) -> bool {
+    match line.trim_start().split_once(',') {
+        None => false,
+        Some((record_type, _)) => SBS_RECORD_TYPES.contains(&record_type),
+    }
+}
+
+fn format_date_time(date_time: &Option<NaiveDateTime>) -> String {
+    match date_time {
+        None => ",".to_string(),
+        Some(date_time) => date_time
+            .format(&format!("{},{}", SBS_DATE_FORMAT, "%H:%M:%S%.3f"))
+            .to_string(),
+    }
+}
+
+fn format_field<T: Display>(value: &Option<T>) -> String {
+    match value {
+        None => String::new(),
+        Some(value) => value.to_string(),
+    }
+}
+
+fn format_decimal(value: &Option<f32>, decimals: usize) -> String {
+    match value {
+        None => String::new(),
+        Some(value) => format!("{:.*}", decimals, value),
+    }
+}
+
+fn format_flag(value: &Option<bool>) -> String {
+    match value {
+        None => String::new(),
+        Some(true) => "-1".to_string(),
+        Some(false) => "0".to_string(),
+    }
+}
+
+fn parse_field<T: FromStr>(value: &str, name: &str) -> MessageResult<Option<T>> {
+    match value.is_empty() {
+        true => Ok(None),
+        false => match value.parse::<T>() {
+            Err(_) => Err(message_error(format!("Invalid SBS {}: {}", name, value))),
+            Ok(parsed) => Ok(Some(parsed)),
+        },
+    }
+}
+
+/// BaseStation writes `-1` for a set flag and `0` for a clear one, some decoders write `1` instead of `-1`.
+fn parse_flag(value: &str, name: &str) -> MessageResult<Option<bool>> {
+    match value {
+        "" => Ok(None),
+        "0" => Ok(Some(false)),
+        "-1" | "1" => Ok(Some(true)),
+        _ => Err(message_error(format!(
+            "Invalid SBS {} flag: {}",
+            name, value
+        ))),
+    }
+}
+
+fn parse_date_time(date: &str, time: &str) -> MessageResult<Option<NaiveDateTime>> {
+    if date.is_empty() || time.is_empty() {
+        return Ok(None);
+    }
+    match (
+        NaiveDate::parse_from_str(date, SBS_DATE_FORMAT),
+        NaiveTime::parse_from_str(time, SBS_TIME_FORMAT),
+    ) {
+        (Ok(date), Ok(time)) => Ok(Some(date.and_time(time))),
+        _ => Err(message_error(format!(
+            "Invalid SBS date and time: {} {}",
+            date, time
+        ))),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SbsMessage {
+    pub transmission_type: SbsTransmissionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aircraft_id: Option<u32>,
+    pub hex_ident: String, // ICAO address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flight_id: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generated: Option<NaiveDateTime>, // when the message was generated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logged: Option<NaiveDateTime>, // when the message was logged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callsign: Option<String>, // callsign
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub altitude: Option<i32>, // Mode C altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<f32>, // ground speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<f32>, // track
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f32>, // latitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f32>, // longitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_rate: Option<i32>, // vertical rate, feet per minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub squawk: Option<String>, // squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<bool>, // squawk has changed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<bool>, // emergency squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spi: Option<bool>, // special position identification (ident)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_on_ground: Option<bool>, // ground squat switch
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbsTransmissionType {
+    #[serde(rename = "identification")]
+    Identification,
+    #[serde(rename = "surface_position")]
+    SurfacePosition,
+    #[serde(rename = "airborne_position")]
+    AirbornePosition,
+    #[serde(rename = "airborne_velocity")]
+    AirborneVelocity,
+    #[serde(rename = "surveillance_altitude")]
+    SurveillanceAltitude,
+    #[serde(rename = "surveillance_identity")]
+    SurveillanceIdentity,
+    #[serde(rename = "air_to_air")]
+    AirToAir,
+    #[serde(rename = "all_call_reply")]
+    AllCallReply,
+}
+
+impl SbsMessage {
+    /// Splits an `ADSBJsonMessage` into the `MSG` lines readsb sends on its BaseStation port.
+    /// BaseStation has no columns for the accuracy categories or the autopilot settings, so those are dropped.
+    ///
+    /// An identification message is produced when the aircraft has a callsign, a surface or airborne position message when it has a position,
+    /// a velocity message when it has a ground speed or track, and a surveillance identity message when it has a squawk.
+    /// The generated time is when the data was last received, derived from `now` less `seen` or `seen_pos`, and the logged time is `now`.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> Vec<SbsMessage> {
+        let (altitude, is_on_ground) = match &message.alt_baro {
+            Some(Altitude::I32(altitude)) => (Some(*altitude), Some(false)),
+            Some(Altitude::Ground(_)) => (None, Some(true)),
+            None => (None, None),
+        };
+        let emergency: Option<bool> = message
+            .emergency
+            .as_ref()
+            .map(|emergency| emergency != "none");
+        let alert: Option<bool> = message.alert.map(|alert| alert != 0);
+        let spi: Option<bool> = message.spi.map(|spi| spi != 0);
+        let logged: Option<NaiveDateTime> = timestamp_to_date_time(message.now);
+        let template: SbsMessage = SbsMessage {
+            transmission_type: SbsTransmissionType::Identification,
+            session_id: Some(1),
+            aircraft_id: Some(1),
+            hex_ident: message.hex.to_uppercase(),
+            flight_id: Some(1),
+            generated: timestamp_to_date_time(message.now - message.seen),
+            logged,
+            callsign: None,
+            altitude: None,
+            ground_speed: None,
+            track: None,
+            lat: None,
+            lon: None,
+            vertical_rate: None,
+            squawk: None,
+            alert: None,
+            emergency: None,
+            spi: None,
+            is_on_ground,
+        };
+        let mut messages: Vec<SbsMessage> = Vec::new();
+        let callsign: Option<&str> = message
+            .flight
+            .as_deref()
+            .map(str::trim)
+            .filter(|flight| !flight.is_empty());
+        if let Some(callsign) = callsign {
+            messages.push(SbsMessage {
+                callsign: Some(callsign.to_string()),
+                ..template.clone()
+            });
+        }
+        if let Some((lat, lon)) = message.position() {
+            let seen_pos: f64 = message.seen_pos.unwrap_or(message.seen);
+            let position: SbsMessage = SbsMessage {
+                generated: timestamp_to_date_time(message.now - seen_pos),
+                lat: Some(lat),
+                lon: Some(lon),
+                ..template.clone()
+            };
+            messages.push(match is_on_ground {
+                Some(true) => SbsMessage {
+                    transmission_type: SbsTransmissionType::SurfacePosition,
+                    ground_speed: message.gs,
+                    track: message.track,
+                    ..position
+                },
+                _ => SbsMessage {
+                    transmission_type: SbsTransmissionType::AirbornePosition,
+                    altitude,
+                    alert,
+                    emergency,
+                    spi,
+                    ..position
+                },
+            });
+        }
+        if message.gs.is_some() || message.track.is_some() {
+            messages.push(SbsMessage {
+                transmission_type: SbsTransmissionType::AirborneVelocity,
+                ground_speed: message.gs,
+                track: message.track,
+                vertical_rate: message.baro_rate.or(message.geom_rate),
+                ..template.clone()
+            });
+        }
+        if let Some(squawk) = &message.squawk {
+            messages.push(SbsMessage {
+                transmission_type: SbsTransmissionType::SurveillanceIdentity,
+                altitude,
+                squawk: Some(squawk.to_string()),
+                alert,
+                emergency,
+                spi,
+                ..template
+            });
+        }
+        messages
+    }
+
+    /// Converts `SbsMessage` to a BaseStation `MSG` line.
+    pub fn to_sbs_string(&self) -> String {
+        format!(
+            "MSG,{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.transmission_type.number(),
+            format_field(&self.session_id),
+            format_field(&self.aircraft_id),
+            self.hex_ident,
+            format_field(&self.flight_id),
+            format_date_time(&self.generated),
+            format_date_time(&self.logged),
+            format_field(&self.callsign),
+            format_field(&self.altitude),
+            format_decimal(&self.ground_speed, 1),
+            format_decimal(&self.track, 1),
+            format_decimal(&self.lat, 5),
+            format_decimal(&self.lon, 5),
+            format_field(&self.vertical_rate),
+            format_field(&self.squawk),
+            format_flag(&self.alert),
+            format_flag(&self.emergency),
+            format_flag(&self.spi),
+            format_flag(&self.is_on_ground),
+        )
+    }
+
+    /// Converts `SbsMessage` to a BaseStation `MSG` line and appends a `\r\n` to the end, as BaseStation does.
+    pub fn to_sbs_string_newline(&self) -> String {
+        format!("{}\r\n", self.to_sbs_string())
+    }
+}
+
+impl SbsTransmissionType {
+    /// Returns the transmission type for the number in the second column of a `MSG` line.
+    pub fn from_number(number: u8) -> Option<Self> {
+        match number {
+            1 => Some(SbsTransmissionType::Identification),
+            2 => Some(SbsTransmissionType::SurfacePosition),
+            3 => Some(SbsTransmissionType::AirbornePosition),
+            4 => Some(SbsTransmissionType::AirborneVelocity),
+            5 => Some(SbsTransmissionType::SurveillanceAltitude),
+            6 => Some(SbsTransmissionType::SurveillanceIdentity),
+            7 => Some(SbsTransmissionType::AirToAir),
+            8 => Some(SbsTransmissionType::AllCallReply),
+            _ => None,
+        }
+    }
+
+    /// Returns the number written in the second column of a `MSG` line.
+    pub fn number(&self) -> u8 {
+        match self {
+            SbsTransmissionType::Identification => 1,
+            SbsTransmissionType::SurfacePosition => 2,
+            SbsTransmissionType::AirbornePosition => 3,
+            SbsTransmissionType::AirborneVelocity => 4,
+            SbsTransmissionType::SurveillanceAltitude => 5,
+            SbsTransmissionType::SurveillanceIdentity => 6,
+            SbsTransmissionType::AirToAir => 7,
+            SbsTransmissionType::AllCallReply => 8,
+        }
+    }
+}
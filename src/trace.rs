@@ -0,0 +1,215 @@
+use crate::adsb_json::{Altitude, NavModes, SilType};
+use crate::{message_error, MessageResult};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Set on a point when no position was received for 20 seconds before it.
+pub const TRACE_FLAG_STALE: u8 = 1;
+/// Set on the first point of a new leg, between a landing and the following takeoff.
+pub const TRACE_FLAG_NEW_LEG: u8 = 2;
+/// Set when the vertical rate of a point is geometric rather than barometric.
+pub const TRACE_FLAG_GEOMETRIC_RATE: u8 = 4;
+/// Set when the altitude of a point is geometric rather than barometric.
+pub const TRACE_FLAG_GEOMETRIC_ALTITUDE: u8 = 8;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be the JSON contents of a `trace_full_<hex>.json` or `trace_recent_<hex>.json` file, and will not consume the source.
+/// readsb writes these files gzip compressed, so they have to be decompressed first.
+pub trait NewTraceFile {
+    fn to_trace(&self) -> MessageResult<TraceFile>;
+}
+
+/// Implementing `.to_trace()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewTraceFile for String {
+    fn to_trace(&self) -> MessageResult<TraceFile> {
+        serde_json::from_str(self)
+    }
+}
+
+/// Supporting `.to_trace()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewTraceFile for str {
+    fn to_trace(&self) -> MessageResult<TraceFile> {
+        serde_json::from_str(self)
+    }
+}
+
+/// The history of a single aircraft written by readsb for the tar1090 globe view.
+#[derive(Debug, Clone)]
+pub struct TraceFile {
+    pub icao: String,                  // ICAO address
+    pub registration: Option<String>,  // registration
+    pub aircraft_type: Option<String>, // aircraft type
+    pub description: Option<String>,   // aircraft type description
+    pub dbflags: Option<i32>,
+    pub timestamp: f64,         // Unix timestamp the point offsets are relative to
+    pub trace: Vec<TracePoint>, // points, oldest first
+}
+
+#[derive(Debug, Clone)]
+pub struct TracePoint {
+    pub timestamp: f64, // Unix timestamp of the point
+    pub lat: f32,       // latitude
+    pub lon: f32,       // longitude
+    pub altitude: Option<Altitude>,
+    pub gs: Option<f32>,    // ground speed
+    pub track: Option<f32>, // track, or true heading on the ground
+    pub flags: u8,          // TRACE_FLAG_* bits
+    pub vertical_rate: Option<i32>,
+    pub details: Option<TraceDetails>, // aircraft state, written periodically
+    pub source: Option<String>,        // type of the position, such as adsb_icao or mlat
+    pub alt_geom: Option<i32>,
+    pub geom_rate: Option<i32>,
+    pub ias: Option<i32>,  // indicated air speed, knots
+    pub roll: Option<f32>, // roll, degrees, negative is left roll
+}
+
+/// The aircraft state readsb attaches to some trace points.
+///
+/// It holds the same fields as an `aircraft.json` entry, without those already in the point.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TraceDetails {
+    #[serde(rename = "type")]
+    pub adsb_type: Option<String>,
+    pub flight: Option<String>,
+    pub alt_geom: Option<i32>,
+    pub ias: Option<i32>,
+    pub tas: Option<i32>,
+    pub mach: Option<f32>,
+    pub wd: Option<i32>,
+    pub ws: Option<i32>,
+    pub oat: Option<i32>,
+    pub tat: Option<i32>,
+    pub track_rate: Option<f32>,
+    pub roll: Option<f32>,
+    pub mag_heading: Option<f32>,
+    pub true_heading: Option<f32>,
+    pub baro_rate: Option<i32>,
+    pub geom_rate: Option<i32>,
+    pub squawk: Option<String>,
+    pub emergency: Option<String>,
+    pub category: Option<String>,
+    pub nav_qnh: Option<f32>,
+    pub nav_altitude_mcp: Option<i32>,
+    pub nav_altitude_fms: Option<i32>,
+    pub nav_heading: Option<f32>,
+    pub nav_modes: Option<Vec<NavModes>>,
+    pub nic: Option<i32>,
+    pub rc: Option<i32>,
+    pub version: Option<i32>,
+    pub nic_baro: Option<i8>,
+    pub nac_p: Option<i8>,
+    pub nac_v: Option<i8>,
+    pub sil: Option<i8>,
+    pub sil_type: Option<SilType>,
+    pub gva: Option<i8>,
+    pub sda: Option<i8>,
+    pub alert: Option<i8>,
+    pub spi: Option<i8>,
+    pub rssi: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct RawTraceFile {
+    icao: String,
+    r: Option<String>,
+    t: Option<String>,
+    desc: Option<String>,
+    #[serde(rename = "dbFlags")]
+    dbflags: Option<i32>,
+    timestamp: f64,
+    trace: Vec<Vec<Value>>,
+}
+
+impl<'de> Deserialize<'de> for TraceFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw_trace: RawTraceFile = RawTraceFile::deserialize(deserializer)?;
+        let mut trace: Vec<TracePoint> = Vec::with_capacity(raw_trace.trace.len());
+        for point in &raw_trace.trace {
+            match TracePoint::from_values(raw_trace.timestamp, point) {
+                Err(point_error) => return Err(D::Error::custom(point_error)),
+                Ok(point) => trace.push(point),
+            }
+        }
+        Ok(TraceFile {
+            icao: raw_trace.icao,
+            registration: raw_trace.r,
+            aircraft_type: raw_trace.t,
+            description: raw_trace.desc,
+            dbflags: raw_trace.dbflags,
+            timestamp: raw_trace.timestamp,
+            trace,
+        })
+    }
+}
+
+impl TracePoint {
+    /// Decodes one entry of the `trace` array, whose first element is an offset in seconds from `base_timestamp`.
+    fn from_values(base_timestamp: f64, values: &[Value]) -> MessageResult<TracePoint> {
+        if values.len() < 8 {
+            return Err(message_error(format!(
+                "Trace point has {} values, expected at least 8: {:?}",
+                values.len(),
+                values
+            )));
+        }
+        let required = |index: usize, name: &str| -> MessageResult<f64> {
+            match values[index].as_f64() {
+                None => Err(message_error(format!(
+                    "Trace point has an invalid {}: {}",
+                    name, values[index]
+                ))),
+                Some(value) => Ok(value),
+            }
+        };
+        Ok(TracePoint {
+            timestamp: base_timestamp + required(0, "time offset")?,
+            lat: required(1, "latitude")? as f32,
+            lon: required(2, "longitude")? as f32,
+            altitude: optional(values, 3)?,
+            gs: optional(values, 4)?,
+            track: optional(values, 5)?,
+            flags: optional(values, 6)?.unwrap_or_default(),
+            vertical_rate: optional(values, 7)?,
+            details: optional(values, 8)?,
+            source: optional(values, 9)?,
+            alt_geom: optional(values, 10)?,
+            geom_rate: optional(values, 11)?,
+            ias: optional(values, 12)?,
+            roll: optional(values, 13)?,
+        })
+    }
+
+    /// Returns `true` if no position was received for 20 seconds before this point.
+    pub fn is_stale(&self) -> bool {
+        self.flags & TRACE_FLAG_STALE != 0
+    }
+
+    /// Returns `true` if this point starts a new leg.
+    pub fn is_new_leg(&self) -> bool {
+        self.flags & TRACE_FLAG_NEW_LEG != 0
+    }
+
+    /// Returns `true` if `vertical_rate` is geometric rather than barometric.
+    pub fn is_geometric_rate(&self) -> bool {
+        self.flags & TRACE_FLAG_GEOMETRIC_RATE != 0
+    }
+
+    /// Returns `true` if `altitude` is geometric rather than barometric.
+    pub fn is_geometric_altitude(&self) -> bool {
+        self.flags & TRACE_FLAG_GEOMETRIC_ALTITUDE != 0
+    }
+}
+
+/// Decodes the value at `index`, treating `null` and values past the end of the point as missing.
+fn optional<T: DeserializeOwned>(values: &[Value], index: usize) -> MessageResult<Option<T>> {
+    match values.get(index) {
+        None => Ok(None),
+        Some(value) => serde_json::from_value(value.clone()),
+    }
+}
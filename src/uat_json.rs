@@ -0,0 +1,207 @@
+use crate::MessageResult;
+use serde::{Deserialize, Serialize};
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single dump978-fa JSON line and have support for providing a `str`, and will not consume the source.
+pub trait NewUatJsonMessage {
+    fn to_uat_json(&self) -> MessageResult<UatJsonMessage>;
+}
+
+/// Implementing `.to_uat_json()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewUatJsonMessage for String {
+    fn to_uat_json(&self) -> MessageResult<UatJsonMessage> {
+        serde_json::from_str(self)
+    }
+}
+
+/// Supporting `.to_uat_json()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewUatJsonMessage for str {
+    fn to_uat_json(&self) -> MessageResult<UatJsonMessage> {
+        serde_json::from_str(self)
+    }
+}
+
+/// A 978MHz UAT downlink message, as written by dump978-fa.
+///
+/// dump978-fa only writes the fields present in the payload of the received message,
+/// so everything other than the address is optional.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatJsonMessage {
+    pub address: String,           // ICAO or other address
+    pub address_qualifier: String, // type of the address, such as adsb_icao or tisb_trackfile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<UatMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<UatPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic: Option<i32>, // Navigation Integrity Category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub horizontal_containment: Option<f64>, // Radius of Containment, meter
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airground_state: Option<String>, // airborne, supersonic or ground
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure_altitude: Option<i32>, // barometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geometric_altitude: Option<i32>, // geometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub north_velocity: Option<i32>, // knots, negative is south
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub east_velocity: Option<i32>, // knots, negative is west
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_velocity_barometric: Option<i32>, // feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertical_velocity_geometric: Option<i32>, // feet/minute
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ground_speed: Option<f32>, // knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_track: Option<f32>, // degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub magnetic_heading: Option<f32>, // degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub true_heading: Option<f32>, // degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aircraft_size: Option<UatAircraftSize>, // on the ground only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_lateral_offset: Option<i32>, // meters, negative is left
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_longitudinal_offset: Option<i32>, // meters behind the nose
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gps_position_offset_applied: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utc_coupled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tisb_site_id: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emitter_category: Option<String>, // category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callsign: Option<String>, // callsign
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flightplan_id: Option<String>, // squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emergency: Option<String>, // emergency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uat_version: Option<i32>, // MOPS version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil: Option<i8>, // Source Integrity Level
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sil_supplement: Option<String>, // per_hour or per_sample
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transmit_mso: Option<i32>, // Message Start Opportunity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sda: Option<i8>, // System Design Assurance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nac_p: Option<i8>, // Navigation Accuracy Category for Position
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nac_v: Option<i8>, // Navigation Accuracy Category for Velocity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_baro: Option<i8>, // Navigation Integrity Category for Barometric Altitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gva: Option<i8>, // Geometric Vertical Accuracy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub single_antenna: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nic_supplement: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capability_codes: Option<UatCapabilityCodes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operational_modes: Option<UatOperationalModes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude_type: Option<String>, // mcp_fcu or fms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude_mcp: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_altitude_fms: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub barometric_pressure_setting: Option<f32>, // hPa
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_heading: Option<f32>, // degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode_indicators: Option<UatModeIndicators>,
+}
+
+/// Reception details dump978-fa adds to each message.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub received_at: Option<f64>, // Unix timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rssi: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<i32>, // number of corrected Reed-Solomon errors
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatPosition {
+    pub lat: f64, // latitude
+    pub lon: f64, // longitude
+}
+
+/// Length and width of an aircraft on the ground, in meters.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatAircraftSize {
+    pub length: f32,
+    pub width: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatCapabilityCodes {
+    pub uat_in: bool,
+    pub es_in: bool,
+    pub tcas_operational: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatOperationalModes {
+    pub tcas_ra_active: bool,
+    pub ident_active: bool,
+    pub atc_services: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UatModeIndicators {
+    pub autopilot: bool,
+    pub vnav: bool,
+    pub altitude_hold: bool,
+    pub approach: bool,
+    pub lnav: bool,
+}
+
+impl UatJsonMessage {
+    /// Converts `UatJsonMessage` to `String`.
+    pub fn to_string(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `UatJsonMessage` to `String` and appends a `\n` to the end.
+    pub fn to_string_newline(&self) -> MessageResult<String> {
+        match serde_json::to_string(self) {
+            Err(to_string_error) => Err(to_string_error),
+            Ok(string) => Ok(format!("{}\n", string)),
+        }
+    }
+
+    /// Converts `UatJsonMessage` to a `String` encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
+        match self.to_string() {
+            Err(conversion_failed) => Err(conversion_failed),
+            Ok(string) => Ok(string.into_bytes()),
+        }
+    }
+
+    /// Converts `UatJsonMessage` to a `String` terminated with a `\n` and encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes_newline(&self) -> MessageResult<Vec<u8>> {
+        match self.to_string_newline() {
+            Err(conversion_failed) => Err(conversion_failed),
+            Ok(string) => Ok(string.into_bytes()),
+        }
+    }
+}
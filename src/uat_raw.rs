@@ -0,0 +1,344 @@
+use crate::uat_json::{
+    UatAircraftSize, UatCapabilityCodes, UatJsonMessage, UatMetadata, UatOperationalModes,
+    UatPosition,
+};
+use crate::{decode_hex, message_error, MessageResult};
+
+/// Length of a basic UAT ADS-B payload, which only carries the header and state vector.
+pub const UAT_BASIC_LENGTH: usize = 18;
+/// Length of a long UAT ADS-B payload.
+pub const UAT_LONG_LENGTH: usize = 34;
+
+/// Characters of the base 40 encoding used for callsigns in the mode status element.
+const UAT_BASE40_ALPHABET: &[u8; 40] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ  ..";
+
+const UAT_ADDRESS_QUALIFIERS: [&str; 8] = [
+    "adsb_icao",
+    "adsb_other",
+    "tisb_icao",
+    "tisb_trackfile",
+    "vehicle",
+    "fixed_beacon",
+    "adsr_other",
+    "reserved",
+];
+
+const UAT_AIRGROUND_STATES: [&str; 4] = ["airborne", "supersonic", "ground", "reserved"];
+
+const UAT_EMERGENCIES: [&str; 8] = [
+    "none", "general", "medical", "minfuel", "nordo", "unlawful", "downed", "reserved",
+];
+
+/// Aircraft widths in meters, indexed by the length code then the width bit of the A/V size code.
+const UAT_AIRCRAFT_WIDTHS: [[f32; 2]; 8] = [
+    [11.5, 23.0],
+    [28.5, 34.0],
+    [33.0, 38.0],
+    [39.5, 45.0],
+    [45.0, 52.0],
+    [59.5, 67.0],
+    [72.5, 80.0],
+    [80.0, 90.0],
+];
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a single raw dump978-fa downlink line, `-<payload>;rs=<errors>;rssi=<rssi>;t=<timestamp>;`, and will not consume the source.
+///
+/// The header, state vector, mode status and auxiliary state vector elements are decoded, as dump978-fa does for its JSON output.
+/// Target state and trajectory change elements are not decoded, and uplink lines, starting with `+`, are rejected.
+pub trait NewUatRawMessage {
+    fn to_uat_raw(&self) -> MessageResult<UatJsonMessage>;
+}
+
+/// Implementing `.to_uat_raw()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewUatRawMessage for String {
+    fn to_uat_raw(&self) -> MessageResult<UatJsonMessage> {
+        self.as_str().to_uat_raw()
+    }
+}
+
+/// Supporting `.to_uat_raw()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewUatRawMessage for str {
+    fn to_uat_raw(&self) -> MessageResult<UatJsonMessage> {
+        let line: &str = self.trim();
+        let mut fields = match line.strip_prefix('-') {
+            None => {
+                return Err(message_error(format!(
+                    "Raw UAT downlink line must start with '-': {}",
+                    line
+                )))
+            }
+            Some(body) => body.split(';'),
+        };
+        let payload: Vec<u8> = decode_hex(fields.next().unwrap_or_default())?;
+        let mut message: UatJsonMessage = UatJsonMessage::from_payload(&payload)?;
+        message.metadata = parse_metadata(fields);
+        Ok(message)
+    }
+}
+
+impl UatJsonMessage {
+    /// Decodes a basic or long UAT ADS-B payload, without its Reed-Solomon parity.
+    pub fn from_payload(payload: &[u8]) -> MessageResult<UatJsonMessage> {
+        if payload.len() != UAT_BASIC_LENGTH && payload.len() != UAT_LONG_LENGTH {
+            return Err(message_error(format!(
+                "UAT downlink payload has an invalid length of {} bytes",
+                payload.len()
+            )));
+        }
+        let payload_type: u8 = payload[0] >> 3;
+        let address_qualifier: usize = (payload[0] & 0x07) as usize;
+        let mut message: UatJsonMessage = UatJsonMessage {
+            address: format!("{:02x}{:02x}{:02x}", payload[1], payload[2], payload[3]),
+            address_qualifier: UAT_ADDRESS_QUALIFIERS[address_qualifier].to_string(),
+            ..Default::default()
+        };
+        if payload_type <= 10 {
+            message.decode_state_vector(payload, matches!(address_qualifier, 2 | 3));
+        }
+        if payload.len() == UAT_LONG_LENGTH {
+            if matches!(payload_type, 1 | 3) {
+                message.decode_mode_status(payload);
+            }
+            if matches!(payload_type, 1 | 2 | 5 | 6) {
+                message.decode_auxiliary_state_vector(payload);
+            }
+        }
+        Ok(message)
+    }
+
+    /// Decodes the state vector element, bytes 4 to 16.
+    fn decode_state_vector(&mut self, payload: &[u8], is_tisb: bool) {
+        let (raw_lat, raw_lon) = raw_lat_lon(&payload[4..10]);
+        let nic: u8 = payload[11] & 0x0f;
+        if raw_lat != 0 || raw_lon != 0 || nic != 0 {
+            let position: UatPosition = decode_lat_lon(raw_lat, raw_lon);
+            self.position = Some(UatPosition {
+                lat: round_to(position.lat, 5),
+                lon: round_to(position.lon, 5),
+            });
+            self.nic = Some(nic as i32);
+            self.horizontal_containment = horizontal_containment(nic);
+        }
+        let raw_altitude: u16 = (payload[10] as u16) << 4 | (payload[11] as u16) >> 4;
+        match (payload[9] & 0x01 != 0, decode_altitude(raw_altitude)) {
+            (_, None) => {}
+            (true, altitude) => self.geometric_altitude = altitude,
+            (false, altitude) => self.pressure_altitude = altitude,
+        }
+        let airground_state: usize = (payload[12] >> 6) as usize;
+        self.airground_state = Some(UAT_AIRGROUND_STATES[airground_state].to_string());
+        let first: u16 = (payload[12] as u16 & 0x1f) << 6 | (payload[13] as u16) >> 2;
+        let second: u16 = (payload[13] as u16 & 0x03) << 9
+            | (payload[14] as u16) << 1
+            | (payload[15] as u16) >> 7;
+        match airground_state {
+            0 | 1 => {
+                let scale: i32 = if airground_state == 1 { 4 } else { 1 };
+                self.north_velocity = decode_velocity(first).map(|velocity| velocity * scale);
+                self.east_velocity = decode_velocity(second).map(|velocity| velocity * scale);
+                if let (Some(north), Some(east)) = (self.north_velocity, self.east_velocity) {
+                    let (north, east) = (north as f32, east as f32);
+                    self.ground_speed = Some(round_to(north.hypot(east) as f64, 1) as f32);
+                    self.true_track = Some(round_to(
+                        east.atan2(north).to_degrees().rem_euclid(360.0) as f64,
+                        2,
+                    ) as f32);
+                }
+                let raw_vertical: u16 =
+                    (payload[15] as u16 & 0x7f) << 4 | (payload[16] as u16) >> 4;
+                if raw_vertical & 0x1ff != 0 {
+                    let rate: i32 = ((raw_vertical & 0x1ff) as i32 - 1) * 64;
+                    let rate: i32 = if raw_vertical & 0x200 != 0 {
+                        -rate
+                    } else {
+                        rate
+                    };
+                    match raw_vertical & 0x400 != 0 {
+                        true => self.vertical_velocity_barometric = Some(rate),
+                        false => self.vertical_velocity_geometric = Some(rate),
+                    }
+                }
+            }
+            2 => {
+                if first & 0x3ff != 0 {
+                    self.ground_speed = Some(((first & 0x3ff) - 1) as f32);
+                }
+                let angle: f32 = round_to((second & 0x1ff) as f64 * 360.0 / 512.0, 2) as f32;
+                match (second >> 9) & 0x03 {
+                    1 => self.true_track = Some(angle),
+                    2 => self.magnetic_heading = Some(angle),
+                    3 => self.true_heading = Some(angle),
+                    _ => {}
+                }
+                let size_code: usize = ((payload[15] >> 3) & 0x0f) as usize;
+                if size_code != 0 {
+                    self.aircraft_size = Some(UatAircraftSize {
+                        length: 15.0 + 10.0 * (size_code >> 1) as f32,
+                        width: UAT_AIRCRAFT_WIDTHS[size_code >> 1][size_code & 0x01],
+                    });
+                }
+                self.gps_position_offset_applied = Some(payload[15] & 0x04 != 0);
+            }
+            _ => {}
+        }
+        match is_tisb {
+            true => self.tisb_site_id = Some((payload[16] & 0x0f) as i32),
+            false => self.utc_coupled = Some(payload[16] & 0x08 != 0),
+        }
+    }
+
+    /// Decodes the mode status element, bytes 17 to 28.
+    fn decode_mode_status(&mut self, payload: &[u8]) {
+        let words: Vec<usize> = payload[17..23]
+            .chunks(2)
+            .map(|pair| (pair[0] as usize) << 8 | pair[1] as usize)
+            .collect();
+        let category: usize = (words[0] / 1600) % 40;
+        self.emitter_category = Some(format!(
+            "{}{}",
+            (b'A' + (category / 8) as u8) as char,
+            category % 8
+        ));
+        let identity: String = [
+            words[0] / 40,
+            words[0],
+            words[1] / 1600,
+            words[1] / 40,
+            words[1],
+            words[2] / 1600,
+            words[2] / 40,
+            words[2],
+        ]
+        .iter()
+        .map(|value| UAT_BASE40_ALPHABET[value % 40] as char)
+        .collect::<String>()
+        .trim_end()
+        .to_string();
+        if !identity.is_empty() {
+            match payload[26] & 0x02 != 0 {
+                true => self.callsign = Some(identity),
+                false => self.flightplan_id = Some(identity),
+            }
+        }
+        self.emergency = Some(UAT_EMERGENCIES[(payload[23] >> 5) as usize].to_string());
+        self.uat_version = Some(((payload[23] >> 2) & 0x07) as i32);
+        self.sil = Some((payload[23] & 0x03) as i8);
+        self.transmit_mso = Some((payload[24] >> 2) as i32);
+        self.sda = Some((payload[24] & 0x03) as i8);
+        self.nac_p = Some((payload[25] >> 4) as i8);
+        self.nac_v = Some(((payload[25] >> 1) & 0x07) as i8);
+        self.nic_baro = Some((payload[25] & 0x01) as i8);
+        self.capability_codes = Some(UatCapabilityCodes {
+            uat_in: payload[26] & 0x80 != 0,
+            es_in: payload[26] & 0x40 != 0,
+            tcas_operational: payload[26] & 0x20 != 0,
+        });
+        self.operational_modes = Some(UatOperationalModes {
+            tcas_ra_active: payload[26] & 0x10 != 0,
+            ident_active: payload[26] & 0x08 != 0,
+            atc_services: payload[26] & 0x04 != 0,
+        });
+        self.sil_supplement = Some(
+            match payload[27] & 0x80 != 0 {
+                true => "per_sample",
+                false => "per_hour",
+            }
+            .to_string(),
+        );
+        self.gva = Some(((payload[27] >> 5) & 0x03) as i8);
+        self.single_antenna = Some(payload[27] & 0x10 != 0);
+        self.nic_supplement = Some(payload[27] & 0x08 != 0);
+    }
+
+    /// Decodes the auxiliary state vector element, bytes 29 to 33, which holds the altitude of the type not sent in the state vector.
+    fn decode_auxiliary_state_vector(&mut self, payload: &[u8]) {
+        let raw_altitude: u16 = (payload[29] as u16) << 4 | (payload[30] as u16) >> 4;
+        match payload[9] & 0x01 != 0 {
+            true => self.pressure_altitude = decode_altitude(raw_altitude),
+            false => self.geometric_altitude = decode_altitude(raw_altitude),
+        }
+    }
+}
+
+/// Decodes a 12 bit altitude in 25 foot steps from -1000 feet, where 0 means no altitude.
+fn decode_altitude(raw_altitude: u16) -> Option<i32> {
+    match raw_altitude {
+        0 => None,
+        _ => Some((raw_altitude as i32 - 1) * 25 - 1000),
+    }
+}
+
+/// Parses the `rs=`, `rssi=` and `t=` fields following the payload of a raw dump978-fa line.
+///
+/// Returns `None` if none of them are present.
+pub(crate) fn parse_metadata<'a, I: Iterator<Item = &'a str>>(fields: I) -> Option<UatMetadata> {
+    let mut metadata: UatMetadata = UatMetadata::default();
+    for field in fields.filter(|field| !field.is_empty()) {
+        match field.split_once('=') {
+            Some(("rs", errors)) => metadata.errors = errors.parse().ok(),
+            Some(("rssi", rssi)) => metadata.rssi = rssi.parse().ok(),
+            Some(("t", received_at)) => metadata.received_at = received_at.parse().ok(),
+            _ => trace!("Ignoring raw UAT field {}", field),
+        }
+    }
+    Some(metadata).filter(|metadata| *metadata != UatMetadata::default())
+}
+
+/// Reads the 23 bit latitude and 24 bit longitude packed into the first 6 bytes of `data`,
+/// as they are laid out in both the downlink state vector and the uplink header.
+pub(crate) fn raw_lat_lon(data: &[u8]) -> (u32, u32) {
+    let raw_lat: u32 = (data[0] as u32) << 15 | (data[1] as u32) << 7 | (data[2] as u32) >> 1;
+    let raw_lon: u32 = (data[2] as u32 & 0x01) << 23
+        | (data[3] as u32) << 15
+        | (data[4] as u32) << 7
+        | (data[5] as u32) >> 1;
+    (raw_lat, raw_lon)
+}
+
+/// Scales a raw latitude and longitude, in units of 360 / 2^24 degrees, to signed degrees.
+pub(crate) fn decode_lat_lon(raw_lat: u32, raw_lon: u32) -> UatPosition {
+    let lat: f64 = raw_lat as f64 * 360.0 / 16_777_216.0;
+    let lon: f64 = raw_lon as f64 * 360.0 / 16_777_216.0;
+    UatPosition {
+        lat: if lat > 90.0 { lat - 180.0 } else { lat },
+        lon: if lon > 180.0 { lon - 360.0 } else { lon },
+    }
+}
+
+/// Decodes an 11 bit sign and magnitude velocity in knots, where a magnitude of 0 means no velocity.
+fn decode_velocity(raw_velocity: u16) -> Option<i32> {
+    match (raw_velocity & 0x3ff, raw_velocity & 0x400 != 0) {
+        (0, _) => None,
+        (magnitude, true) => Some(1 - magnitude as i32),
+        (magnitude, false) => Some(magnitude as i32 - 1),
+    }
+}
+
+/// Returns the radius of containment in meters for a Navigation Integrity Category.
+fn horizontal_containment(nic: u8) -> Option<f64> {
+    match nic {
+        1 => Some(37040.0),
+        2 => Some(14816.0),
+        3 => Some(7408.0),
+        4 => Some(3704.0),
+        5 => Some(1852.0),
+        6 => Some(1111.2),
+        7 => Some(370.4),
+        8 => Some(185.2),
+        9 => Some(75.0),
+        10 => Some(25.0),
+        11 => Some(7.5),
+        _ => None,
+    }
+}
+
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let scale: f64 = 10f64.powi(decimals);
+    (value * scale).round() / scale
+}
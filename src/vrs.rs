@@ -0,0 +1,314 @@
+use crate::adsb_json::{ADSBJsonMessage, Altitude};
+use crate::aircraft_json::AircraftSnapshot;
+use crate::MessageResult;
+use serde::{Deserialize, Serialize};
+
+/// Kilometres in a nautical mile, VRS reports distances in kilometres where readsb uses nautical miles.
+const KILOMETRES_PER_NAUTICAL_MILE: f32 = 1.852;
+/// Hectopascals in an inch of mercury, VRS reports the pressure setting in inches where readsb uses hectopascals.
+const HECTOPASCALS_PER_INCH_OF_MERCURY: f32 = 33.8639;
+
+/// Trait for performing a decode if you wish to apply it to types other than the defaults done in this library.
+///
+/// The originating data must be a complete Virtual Radar Server `AircraftList.json` document, and will not consume the source.
+pub trait NewVrsAircraftList {
+    fn to_vrs(&self) -> MessageResult<VrsAircraftList>;
+}
+
+/// Implementing `.to_vrs()` for the type `String`.
+///
+/// This does not consume the `String`.
+impl NewVrsAircraftList for String {
+    fn to_vrs(&self) -> MessageResult<VrsAircraftList> {
+        serde_json::from_str(self)
+    }
+}
+
+/// Supporting `.to_vrs()` for the type `str`.
+///
+/// This does not consume the `str`.
+impl NewVrsAircraftList for str {
+    fn to_vrs(&self) -> MessageResult<VrsAircraftList> {
+        serde_json::from_str(self)
+    }
+}
+
+/// A Virtual Radar Server `AircraftList.json` document.
+///
+/// Only the aircraft list and its timing are kept, the feed and display settings VRS adds for its own web site are ignored.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct VrsAircraftList {
+    #[serde(rename = "acList")]
+    pub ac_list: Vec<VrsAircraft>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "totalAc")]
+    pub total_ac: Option<u32>, // number of aircraft tracked, before any filtering
+    #[serde(skip_serializing_if = "Option::is_none", rename = "lastDv")]
+    pub last_dv: Option<String>, // data version, sent back by clients to only receive changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stm: Option<u64>, // Unix timestamp of the document, milliseconds
+}
+
+/// A single aircraft of a VRS aircraft list, using the VRS short keys.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VrsAircraft {
+    pub id: u32,      // unique identifier, the ICAO address as a number
+    pub icao: String, // ICAO address, upper case
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reg: Option<String>, // registration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alt: Option<i32>, // barometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none", rename = "GAlt")]
+    pub g_alt: Option<i32>, // geometric altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none", rename = "InHg")]
+    pub in_hg: Option<f32>, // pressure setting, inches of mercury
+    #[serde(skip_serializing_if = "Option::is_none", rename = "AltT")]
+    pub alt_t: Option<u8>, // altitude type, 0 barometric and 1 geometric
+    #[serde(skip_serializing_if = "Option::is_none", rename = "TAlt")]
+    pub t_alt: Option<i32>, // selected altitude, feet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call: Option<String>, // callsign
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f32>, // latitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long: Option<f32>, // longitude
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pos_time: Option<u64>, // Unix timestamp of the position, milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mlat: Option<bool>, // position from multilateration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tisb: Option<bool>, // position from TIS-B
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spd: Option<f32>, // speed, knots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spd_typ: Option<u8>, // speed type, 0 is ground speed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vsi: Option<i32>, // vertical rate, feet/minute
+    #[serde(skip_serializing_if = "Option::is_none", rename = "VsiT")]
+    pub vsi_t: Option<u8>, // vertical rate type, 0 barometric and 1 geometric
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trak: Option<f32>, // track, degrees
+    #[serde(skip_serializing_if = "Option::is_none", rename = "TrkH")]
+    pub trk_h: Option<bool>, // `trak` is the heading rather than the track
+    #[serde(skip_serializing_if = "Option::is_none", rename = "TTrk")]
+    pub t_trk: Option<f32>, // selected heading, degrees
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sqk: Option<String>, // squawk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<bool>, // emergency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gnd: Option<bool>, // on the ground
+    #[serde(skip_serializing_if = "Option::is_none", rename = "Type")]
+    pub aircraft_type: Option<String>, // aircraft type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mdl: Option<String>, // aircraft model description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op: Option<String>, // operator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<String>, // year of manufacture
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mil: Option<bool>, // military
+    #[serde(skip_serializing_if = "Option::is_none", rename = "CMsgs")]
+    pub c_msgs: Option<i32>, // number of messages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dst: Option<f32>, // distance from the receiver, kilometres
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brng: Option<f32>, // bearing from the receiver, degrees
+}
+
+impl VrsAircraft {
+    /// Fills a VRS aircraft list entry from an `ADSBJsonMessage`, converting the receiver distance to kilometres and the QNH to inches of mercury.
+    ///
+    /// The position time is derived from `now` less `seen_pos`, and non-ICAO addresses lose their `~` prefix as VRS has no equivalent.
+    pub fn from_adsb_json(message: &ADSBJsonMessage) -> VrsAircraft {
+        let icao: String = message.hex.trim_start_matches('~').to_uppercase();
+        let (alt, gnd) = match &message.alt_baro {
+            Some(Altitude::I32(altitude)) => (Some(*altitude), Some(false)),
+            Some(Altitude::Ground(_)) => (None, Some(true)),
+            None => (None, None),
+        };
+        let (vsi, vsi_t) = match (message.baro_rate, message.geom_rate) {
+            (Some(rate), _) => (Some(rate), Some(0)),
+            (None, Some(rate)) => (Some(rate), Some(1)),
+            (None, None) => (None, None),
+        };
+        let (trak, trk_h) = match (message.track, message.true_heading) {
+            (Some(track), _) => (Some(track), Some(false)),
+            (None, Some(heading)) => (Some(heading), Some(true)),
+            (None, None) => (None, None),
+        };
+        VrsAircraft {
+            id: u32::from_str_radix(&icao, 16).unwrap_or_default(),
+            icao,
+            reg: Some(message.aircraft_registration.clone()).filter(|reg| !reg.is_empty()),
+            alt,
+            g_alt: message.alt_geom,
+            in_hg: message
+                .nav_qnh
+                .map(|qnh| (qnh / HECTOPASCALS_PER_INCH_OF_MERCURY * 100.0).round() / 100.0),
+            alt_t: Some(0),
+            t_alt: message.nav_altitude_mcp,
+            call: message
+                .flight
+                .as_deref()
+                .map(str::trim)
+                .filter(|flight| !flight.is_empty())
+                .map(str::to_string),
+            lat: message.lat,
+            long: message.lon,
+            pos_time: message
+                .seen_pos
+                .filter(|_| message.position().is_some())
+                .map(|seen_pos| ((message.now - seen_pos) * 1000.0).round() as u64),
+            mlat: Some(message.is_mlat_position()),
+            tisb: Some(
+                message.adsb_type.starts_with("tisb")
+                    || message.tisb.iter().any(|field| field == "lat"),
+            ),
+            spd: message.gs,
+            spd_typ: message.gs.map(|_| 0),
+            vsi,
+            vsi_t,
+            trak,
+            trk_h,
+            t_trk: message.nav_heading,
+            sqk: message.squawk.clone(),
+            help: message
+                .emergency
+                .as_ref()
+                .map(|emergency| emergency != "none"),
+            gnd,
+            aircraft_type: message.aircraft_type.clone(),
+            mdl: message.description.clone(),
+            op: message.owner_operator.clone(),
+            year: message.year.clone(),
+            mil: message.dbflags.map(|dbflags| dbflags & 1 != 0),
+            c_msgs: Some(message.messages),
+            dst: message
+                .r_dst
+                .map(|r_dst| r_dst * KILOMETRES_PER_NAUTICAL_MILE),
+            brng: message.r_dir,
+        }
+    }
+
+    /// Converts the VRS aircraft to an `ADSBJsonMessage` received at `now`.
+    ///
+    /// Aircraft without a position leave the position fields empty.
+    /// The address type is derived from the `Mlat` and `Tisb` flags, and fields VRS does not track are left empty.
+    pub fn to_adsb_json(&self, now: f64) -> ADSBJsonMessage {
+        let (lat, lon) = match (self.lat, self.long) {
+            (Some(lat), Some(lon)) => (Some(lat), Some(lon)),
+            _ => (None, None),
+        };
+        let (adsb_type, mlat, tisb) = match (self.mlat, self.tisb) {
+            (Some(true), _) => (
+                "mlat",
+                vec!["lat".to_string(), "lon".to_string()],
+                Vec::new(),
+            ),
+            (_, Some(true)) => (
+                "tisb_icao",
+                Vec::new(),
+                vec!["lat".to_string(), "lon".to_string()],
+            ),
+            _ => ("adsb_icao", Vec::new(), Vec::new()),
+        };
+        let alt_baro: Option<Altitude> = match (self.gnd, self.alt) {
+            (Some(true), _) => Some(Altitude::Ground("ground".to_string())),
+            (_, altitude) => altitude.map(Altitude::I32),
+        };
+        let is_geometric_rate: bool = self.vsi_t == Some(1);
+        let is_heading: bool = self.trk_h == Some(true);
+        ADSBJsonMessage {
+            now,
+            hex: self.icao.to_lowercase(),
+            adsb_type: adsb_type.to_string(),
+            flight: self.call.clone(),
+            aircraft_registration: self.reg.clone().unwrap_or_default(),
+            aircraft_type: self.aircraft_type.clone(),
+            description: self.mdl.clone(),
+            owner_operator: self.op.clone(),
+            year: self.year.clone(),
+            alt_baro,
+            alt_geom: self.g_alt,
+            gs: self.spd.filter(|_| self.spd_typ.unwrap_or_default() == 0),
+            track: self.trak.filter(|_| !is_heading),
+            true_heading: self.trak.filter(|_| is_heading),
+            baro_rate: self.vsi.filter(|_| !is_geometric_rate),
+            geom_rate: self.vsi.filter(|_| is_geometric_rate),
+            squawk: self.sqk.clone(),
+            emergency: self
+                .help
+                .map(|help| if help { "general" } else { "none" }.to_string()),
+            nav_qnh: self
+                .in_hg
+                .map(|in_hg| (in_hg * HECTOPASCALS_PER_INCH_OF_MERCURY * 10.0).round() / 10.0),
+            nav_altitude_mcp: self.t_alt,
+            nav_heading: self.t_trk,
+            lat,
+            lon,
+            seen_pos: self
+                .pos_time
+                .filter(|_| lat.is_some())
+                .map(|pos_time| (now - pos_time as f64 / 1000.0).max(0.0)),
+            r_dst: self.dst.map(|dst| dst / KILOMETRES_PER_NAUTICAL_MILE),
+            r_dir: self.brng,
+            mlat,
+            tisb,
+            messages: self.c_msgs.unwrap_or_default(),
+            dbflags: self.mil.map(|mil| mil as i32),
+            ..Default::default()
+        }
+    }
+}
+
+impl VrsAircraftList {
+    /// Builds a VRS aircraft list from an `aircraft.json` snapshot.
+    pub fn from_snapshot(snapshot: &AircraftSnapshot) -> VrsAircraftList {
+        VrsAircraftList {
+            ac_list: snapshot
+                .aircraft
+                .iter()
+                .map(VrsAircraft::from_adsb_json)
+                .collect(),
+            total_ac: Some(snapshot.aircraft.len() as u32),
+            last_dv: None,
+            stm: Some((snapshot.now * 1000.0).round() as u64),
+        }
+    }
+
+    /// Converts the VRS aircraft list to an `aircraft.json` snapshot taken at `stm`.
+    ///
+    /// Aircraft without a position are kept, as they are when reading an `aircraft.json` document.
+    pub fn to_snapshot(&self) -> AircraftSnapshot {
+        let now: f64 = self.stm.unwrap_or_default() as f64 / 1000.0;
+        let aircraft: Vec<ADSBJsonMessage> = self
+            .ac_list
+            .iter()
+            .map(|aircraft| aircraft.to_adsb_json(now))
+            .collect();
+        AircraftSnapshot {
+            now,
+            messages: aircraft
+                .iter()
+                .map(|message| message.messages.max(0) as u64)
+                .sum(),
+            aircraft,
+        }
+    }
+
+    /// Converts `VrsAircraftList` to an `AircraftList.json` `String`.
+    pub fn to_string(&self) -> MessageResult<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Converts `VrsAircraftList` to an `AircraftList.json` `String` encoded as bytes.
+    ///
+    /// The output is returned as a `Vec<u8>`.
+    pub fn to_bytes(&self) -> MessageResult<Vec<u8>> {
+        match self.to_string() {
+            Err(conversion_failed) => Err(conversion_failed),
+            Ok(string) => Ok(string.into_bytes()),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude, JsonDialect};
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use serde_json::Value;
+use std::error::Error;
+use std::fs;
+
+/// This test reads a polled `aircraft.json` document.
+/// It validates that every aircraft is decoded with `now` taken from the document,
+/// that the aircraft without a position is kept without one, and that an entry that cannot be decoded is left out.
+#[test]
+fn test_aircraft_snapshot_parsing() -> Result<(), Box<dyn Error>> {
+    let document: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = document.to_aircraft_snapshot()?;
+    assert_eq!(snapshot.now, 1675869370.1);
+    assert_eq!(snapshot.messages, 81873316);
+    assert_eq!(snapshot.aircraft.len(), 6);
+    for aircraft in &snapshot.aircraft {
+        assert_eq!(aircraft.now, snapshot.now);
+        assert!(aircraft.to_string().as_ref().err().is_none());
+    }
+    assert_eq!(snapshot.aircraft[3].hex, "a4b6f1");
+    assert_eq!(snapshot.aircraft[3].position(), None);
+    assert_eq!(snapshot.aircraft[3].seen_pos, None);
+    assert_eq!(snapshot.aircraft[1].hex, "a0a8da");
+    assert_eq!(snapshot.aircraft[1].ias, Some(262));
+    assert_eq!(snapshot.aircraft[1].mach, Some(0.776));
+    assert_eq!(snapshot.aircraft[1].nav_altitude_fms, Some(34000));
+    let broken: AircraftSnapshot =
+        r#"{"now":1675869370.1,"messages":1,"aircraft":[{"hex":"a05dbc","lat":1.0}]}"#
+            .to_aircraft_snapshot()?;
+    assert!(broken.aircraft.is_empty());
+    Ok(())
+}
+
+/// This test builds a snapshot from messages received at different times with `from_messages()`.
+/// It validates that `seen` and `seen_pos` are relative to the snapshot time,
+/// that the serialised document parses back with the entries left without their own `now`,
+/// and that a decoded document is written back with the same fields, without the defaults filled in when decoding.
+#[test]
+fn test_aircraft_snapshot_serialisation() -> Result<(), Box<dyn Error>> {
+    let document: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let mut messages: Vec<ADSBJsonMessage> = document.to_aircraft_snapshot()?.aircraft;
+    messages[0].now -= 10.0;
+    let snapshot: AircraftSnapshot = AircraftSnapshot::from_messages(1675869372.1, &messages);
+    assert_eq!(snapshot.now, 1675869372.1);
+    assert_eq!(
+        snapshot.messages,
+        messages
+            .iter()
+            .map(|message| message.messages as u64)
+            .sum::<u64>()
+    );
+    assert_eq!(snapshot.aircraft[0].seen, 12.4);
+    assert_eq!(snapshot.aircraft[0].seen_pos, Some(12.0));
+    assert_eq!(snapshot.aircraft[4].seen, 3.8);
+    assert_eq!(snapshot.aircraft[4].seen_pos, Some(4.1));
+    let serialised: Value = serde_json::from_str(&snapshot.to_string()?)?;
+    assert_eq!(serialised["now"], 1675869372.1);
+    assert_eq!(serialised["aircraft"].as_array().map(Vec::len), Some(6));
+    assert!(serialised["aircraft"][0].get("now").is_none());
+    let reparsed: AircraftSnapshot = snapshot.to_string()?.to_aircraft_snapshot()?;
+    assert_eq!(reparsed.aircraft.len(), 6);
+    assert_eq!(reparsed.aircraft[4].seen_pos, Some(4.1));
+
+    let original: Value = serde_json::from_str(&document)?;
+    let round_trip: Value = serde_json::from_str(&document.to_aircraft_snapshot()?.to_string()?)?;
+    for (entry, original_entry) in round_trip["aircraft"]
+        .as_array()
+        .ok_or("Missing aircraft")?
+        .iter()
+        .zip(original["aircraft"].as_array().ok_or("Missing aircraft")?)
+    {
+        let keys: Vec<&String> = entry.as_object().ok_or("Invalid entry")?.keys().collect();
+        let original_keys: Vec<&String> = original_entry
+            .as_object()
+            .ok_or("Invalid entry")?
+            .keys()
+            .collect();
+        assert_eq!(keys, original_keys);
+    }
+    assert_eq!(round_trip["aircraft"][3], original["aircraft"][3]);
+    Ok(())
+}
+
+/// This test reads a dump1090-fa `aircraft.json` document, most of whose entries readsb rules reject.
+/// It validates that the dump1090-fa dialect fills the fields dump1090-fa does not write,
+/// reads the field names used by older builds, and derives the address type when it is missing.
+#[test]
+fn test_dump1090_fa_dialect() -> Result<(), Box<dyn Error>> {
+    let document: String = fs::read_to_string("test_files/aircraft_dump1090fa_01.json")?;
+    assert_eq!(document.to_aircraft_snapshot()?.aircraft.len(), 1);
+    let snapshot: AircraftSnapshot =
+        AircraftSnapshot::from_dialect(&document, JsonDialect::Dump1090Fa)?;
+    assert_eq!(snapshot.aircraft.len(), 4);
+    assert_eq!(snapshot.aircraft[0].adsb_type, "adsb_icao");
+    assert_eq!(snapshot.aircraft[0].aircraft_registration, "");
+    assert_eq!(
+        snapshot.aircraft[0].nav_modes.as_ref().map(Vec::len),
+        Some(4)
+    );
+    assert_eq!(snapshot.aircraft[1].adsb_type, "adsb_icao");
+    assert_eq!(snapshot.aircraft[1].gs, Some(491.0));
+    assert_eq!(snapshot.aircraft[1].baro_rate, Some(0));
+    assert_eq!(snapshot.aircraft[1].rc, None);
+    assert!(matches!(
+        snapshot.aircraft[1].alt_baro,
+        Some(Altitude::I32(39000))
+    ));
+    assert_eq!(snapshot.aircraft[2].adsb_type, "mlat");
+    assert_eq!(snapshot.aircraft[3].hex, "a4b6f1");
+    assert_eq!(snapshot.aircraft[3].position(), None);
+    for aircraft in &snapshot.aircraft {
+        assert_eq!(aircraft.now, snapshot.now);
+        let readsb: ADSBJsonMessage =
+            ADSBJsonMessage::from_dialect(&aircraft.to_string()?, JsonDialect::Readsb)?;
+        assert_eq!(readsb.hex, aircraft.hex);
+    }
+    let record: &str = r#"{"now":1675869370.1,"hex":"ad8895","alt_baro":"ground","gs":12,"lat":36.385712,"lon":-108.133392,"mlat":[],"tisb":[],"messages":98,"seen":2.3,"rssi":-30.2}"#;
+    assert!(ADSBJsonMessage::from_dialect(record, JsonDialect::Readsb).is_err());
+    let message: ADSBJsonMessage = ADSBJsonMessage::from_dialect(record, JsonDialect::Dump1090Fa)?;
+    assert_eq!(message.adsb_type, "unknown");
+    assert!(matches!(message.alt_baro, Some(Altitude::Ground(_))));
+    let no_altitude: &str = r#"{"now":1675869370.1,"hex":"a4b6f1","mlat":[],"tisb":[],"messages":2,"seen":0.4,"rssi":-31.2}"#;
+    let message: ADSBJsonMessage =
+        ADSBJsonMessage::from_dialect(no_altitude, JsonDialect::Dump1090Fa)?;
+    assert!(message.alt_baro.is_none());
+    assert!(!message.to_string()?.contains("alt_baro"));
+    Ok(())
+}
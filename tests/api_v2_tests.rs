@@ -0,0 +1,65 @@
+use adsb_parser::adsb_json::ADSBJsonMessage;
+use adsb_parser::api_v2::{ApiV2Response, NewApiV2Response};
+use std::error::Error;
+use std::fs;
+
+/// This test decodes a saved API v2 response and validates the envelope and the aggregator specific fields of the entries.
+/// It validates that a stale position is only kept in `lastPosition`, and that entries without a current position are kept without one.
+#[test]
+fn test_api_v2_parsing() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/api_v2_01.json")?;
+    let response: ApiV2Response = contents.to_api_v2()?;
+    assert_eq!(response.msg, "No error");
+    assert_eq!(response.now, 1675869370123);
+    assert_eq!(response.total, 4);
+    assert_eq!(response.ctime, 1675869370000);
+    assert_eq!(response.ac.len(), 4);
+    assert!(response
+        .ac
+        .iter()
+        .all(|message| message.now == 1675869370.123));
+
+    let current: &ADSBJsonMessage = &response.ac[0];
+    assert_eq!(current.aircraft_registration, "N12218");
+    assert_eq!(current.description.as_deref(), Some("BOEING 737-800"));
+    assert_eq!(
+        current.owner_operator.as_deref(),
+        Some("UNITED AIRLINES INC")
+    );
+    assert_eq!(current.year.as_deref(), Some("2000"));
+    assert_eq!(current.dst, Some(12.43));
+    assert_eq!(current.dir, Some(241.7));
+    assert!(current.last_position.is_none());
+
+    let stale: &ADSBJsonMessage = &response.ac[1];
+    let last_position = stale.last_position.as_ref().ok_or("Missing lastPosition")?;
+    assert_eq!(last_position.lon, -106.211977);
+    assert_eq!(last_position.seen_pos, 71.2);
+    assert_eq!(stale.position(), None);
+    assert_eq!(stale.seen_pos, None);
+    assert_eq!(stale.rr_lat, Some(37.1));
+    assert_eq!(stale.rr_lon, Some(-106.0));
+
+    let military: &ADSBJsonMessage = &response.ac[2];
+    assert_eq!(military.aircraft_registration, "");
+    assert_eq!(military.dbflags, Some(1));
+    assert_eq!(military.gps_ok_before, Some(1675869300.5));
+    assert_eq!(military.gps_ok_lat, Some(37.61));
+
+    let mode_s: &ADSBJsonMessage = &response.ac[3];
+    assert_eq!(mode_s.hex, "c0ffee");
+    assert_eq!(mode_s.position(), None);
+    assert_eq!(mode_s.rr_lat, Some(37.0));
+
+    let serialised: ApiV2Response = response.to_string()?.to_api_v2()?;
+    assert_eq!(serialised.ac.len(), 4);
+    assert_eq!(serialised.ac[1].lat, None);
+    assert_eq!(
+        serialised.ac[1]
+            .last_position
+            .as_ref()
+            .map(|last_position| last_position.lat),
+        Some(last_position.lat)
+    );
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude, SilType};
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::asterix::cat021::{Cat021Record, NewCat021Records};
+use adsb_parser::asterix::split_data_blocks;
+use std::error::Error;
+use std::fs;
+
+/// This test reads a saved ASTERIX buffer holding a block of another category and a CAT021 block,
+/// validating the decoded data items, that unknown items are skipped, and the conversion to `ADSBJsonMessage`.
+#[test]
+fn test_cat021_decode() -> Result<(), Box<dyn Error>> {
+    let contents: Vec<u8> = fs::read("test_files/asterix_cat021_01.bin")?;
+    assert_eq!(split_data_blocks(&contents)?.len(), 2);
+    let records: Vec<Cat021Record> = contents.to_cat021()?;
+    assert_eq!(records.len(), 2);
+
+    let record: &Cat021Record = &records[0];
+    assert_eq!(record.data_source.ok_or("Missing data source")?.sac, 25);
+    assert_eq!(record.target_address, Some(0x4ca2d1));
+    assert_eq!(record.time_of_applicability_position, Some(55000.5));
+    assert_eq!(record.target_identification.as_deref(), Some("RYR4AB  "));
+    assert_eq!(record.mode_3a.as_deref(), Some("7700"));
+    assert_eq!(record.flight_level, Some(370.0));
+    assert_eq!(record.emitter_category, Some(3));
+
+    let now: f64 = 1675869401.0; // 2023-02-08 15:16:41 UTC, half a second after the position
+    let message: ADSBJsonMessage = record.to_adsb_json(now)?;
+    assert_eq!(message.hex, "4ca2d1");
+    assert_eq!(message.adsb_type, "adsb_icao");
+    assert_eq!(message.flight.as_deref(), Some("RYR4AB  "));
+    assert!(matches!(message.alt_baro, Some(Altitude::I32(37000))));
+    assert_eq!(message.alt_geom, Some(37250));
+    assert!((message.lat.unwrap_or_default() - 51.4775).abs() < 1e-5);
+    assert!((message.lon.unwrap_or_default() + 0.4614).abs() < 1e-5);
+    assert_eq!(message.seen_pos, Some(0.5));
+    assert_eq!(message.nic, Some(8));
+    assert_eq!(message.nac_p, Some(9));
+    assert_eq!(message.nac_v, Some(2));
+    assert_eq!(message.sil, Some(3));
+    assert!(matches!(message.sil_type, SilType::PerHour));
+    assert_eq!(message.sda, Some(2));
+    assert_eq!(message.gva, Some(2));
+    assert_eq!(message.version, Some(2));
+    assert_eq!(message.emergency.as_deref(), Some("general"));
+    assert_eq!(message.baro_rate, Some(-1000));
+    assert!((message.gs.ok_or("Missing gs")? - 450.0).abs() < 0.1);
+    assert_eq!(message.track, Some(90.0));
+    assert_eq!(message.category.as_deref(), Some("A3"));
+    assert_eq!(message.nav_altitude_mcp, Some(38000));
+
+    let no_position: ADSBJsonMessage = records[1].to_adsb_json(now)?;
+    assert_eq!(no_position.position(), None);
+    assert_eq!(no_position.seen_pos, None);
+    Ok(())
+}
+
+/// This test converts an `aircraft.json` snapshot to a CAT021 data block and back,
+/// validating that the round trip keeps the state of each aircraft within the resolution of the data items.
+#[test]
+fn test_cat021_round_trip() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let records: Vec<Cat021Record> = snapshot
+        .aircraft
+        .iter()
+        .filter(|message| message.seen_pos.is_some())
+        .map(|message| Cat021Record::from_adsb_json(message, 25, 201))
+        .collect();
+    let data_block: Vec<u8> = Cat021Record::to_data_block(&records)?;
+    assert_eq!(data_block[0], 21);
+    assert_eq!(
+        u16::from_be_bytes([data_block[1], data_block[2]]) as usize,
+        data_block.len()
+    );
+
+    let decoded: Vec<Cat021Record> = data_block.to_cat021()?;
+    assert_eq!(decoded.len(), records.len());
+    for (original, record) in snapshot
+        .aircraft
+        .iter()
+        .filter(|message| message.seen_pos.is_some())
+        .zip(decoded.iter())
+    {
+        let message: ADSBJsonMessage = record.to_adsb_json(snapshot.now)?;
+        assert_eq!(message.hex, original.hex);
+        assert_eq!(message.flight, original.flight);
+        assert_eq!(message.squawk, original.squawk);
+        assert_eq!(message.category, original.category);
+        assert_eq!(message.nac_p, original.nac_p);
+        assert_eq!(
+            message.nav_altitude_mcp.map(|altitude| altitude / 25),
+            original.nav_altitude_mcp.map(|altitude| altitude / 25)
+        );
+        match (&message.alt_baro, &original.alt_baro) {
+            (Some(Altitude::I32(decoded)), Some(Altitude::I32(original))) => {
+                assert!((decoded - original).abs() <= 25)
+            }
+            (Some(Altitude::Ground(_)), Some(Altitude::Ground(_))) | (None, None) => {}
+            _ => panic!("Altitude changed for {}", original.hex),
+        }
+        assert!((message.lat.unwrap_or_default() - original.lat.unwrap_or_default()).abs() < 1e-5);
+        assert!((message.lon.unwrap_or_default() - original.lon.unwrap_or_default()).abs() < 1e-5);
+        let seen_pos: f64 = message.seen_pos.ok_or("Missing seen_pos")?;
+        assert!((seen_pos - original.seen_pos.ok_or("Missing seen_pos")?).abs() < 0.01);
+        if let (Some(decoded), Some(original)) = (message.gs, original.gs) {
+            assert!((decoded - original).abs() < 0.2);
+        }
+        if let (Some(decoded), Some(original)) = (message.track, original.track) {
+            assert!((decoded - original).abs() < 0.01);
+        }
+    }
+    Ok(())
+}
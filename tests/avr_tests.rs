@@ -0,0 +1,42 @@
+mod common;
+
+use adsb_parser::avr::NewAvrMessage;
+use adsb_parser::beast::{BeastMessage, BeastMessageType};
+use adsb_parser::{ADSBMessage, DecodeMessage};
+use std::error::Error;
+
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test ingests the AVR sample files as a message per line.
+/// It validates that every line decodes through both `to_avr()` and `decode_message()` to the same raw frame.
+#[test]
+fn test_avr_parsing() -> Result<(), Box<dyn Error>> {
+    let avr_messages: Vec<String> = combine_files_of_message_type(MessageType::Avr)?;
+    assert!(!avr_messages.is_empty());
+    for line in avr_messages {
+        let frame: BeastMessage = line.to_avr()?;
+        match line.decode_message()? {
+            ADSBMessage::Beast(decoded) => assert_eq!(decoded, frame),
+            other => panic!("Expected a raw frame for {}, got {:?}", line, other),
+        }
+    }
+    Ok(())
+}
+
+/// This test validates the decoded contents of plain and timestamped AVR lines, and that malformed lines are rejected.
+#[test]
+fn test_avr_frame_contents() -> Result<(), Box<dyn Error>> {
+    let plain: BeastMessage = "*8D4840D6202CC371C32CE0576098;".to_avr()?;
+    assert_eq!(plain.message_type, BeastMessageType::ModeSLong);
+    assert_eq!(plain.timestamp, 0);
+    assert_eq!(plain.data[..4], [0x8d, 0x48, 0x40, 0xd6]);
+    let timestamped: BeastMessage = "@123456789B005D4840D6F8740F;\r\n".to_avr()?;
+    assert_eq!(timestamped.message_type, BeastMessageType::ModeSShort);
+    assert_eq!(timestamped.timestamp, 0x123456789b00);
+    assert_eq!(timestamped.data.len(), 7);
+    assert!("*8D4840D6202CC371C32CE0576098".to_avr().is_err());
+    assert!("*8D4840D6202CC371C32CE05760;".to_avr().is_err());
+    assert!("*8D4840D6202CC371C32CE05760+8;".to_avr().is_err());
+    assert!("@1234;".to_avr().is_err());
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use adsb_parser::beast::{decode_beast_frames, BeastMessage, BeastMessageType, NewBeastMessage};
+use adsb_parser::{ADSBMessage, DecodeMessage};
+use std::error::Error;
+use std::fs;
+
+/// This test reads a captured Beast stream containing leading garbage, escaped bytes and a truncated final frame.
+/// It validates that every complete frame is decoded and that the truncated frame is left unconsumed.
+#[test]
+fn test_beast_stream_decoding() -> Result<(), Box<dyn Error>> {
+    let buffer: Vec<u8> = fs::read("test_files/beast_01.bin")?;
+    let (messages, consumed) = decode_beast_frames(&buffer);
+    assert_eq!(messages.len(), 6);
+    assert_eq!(consumed, buffer.len() - 10);
+    assert_eq!(messages[0].message_type, BeastMessageType::ModeAC);
+    assert_eq!(messages[0].timestamp, 0x00001A2B3C4D);
+    assert_eq!(messages[0].data, vec![0x0e, 0x07]);
+    assert_eq!(messages[1].message_type, BeastMessageType::ModeSShort);
+    assert_eq!(messages[1].timestamp, 0x0000001A0000);
+    assert_eq!(messages[2].message_type, BeastMessageType::ModeSLong);
+    assert_eq!(messages[2].signal_level, 0x1a);
+    assert_eq!(
+        messages[2].data,
+        vec![0x8d, 0x48, 0x40, 0xd6, 0x20, 0x2c, 0xc3, 0x71, 0xc3, 0x2c, 0xe0, 0x57, 0x60, 0x98]
+    );
+    let (remaining, remaining_consumed) = decode_beast_frames(&buffer[consumed..]);
+    assert!(remaining.is_empty());
+    assert_eq!(remaining_consumed, 0);
+    Ok(())
+}
+
+/// This test validates that a single frame decodes through both `to_beast()` and `decode_message()`,
+/// and that incomplete or unknown frames are reported as errors.
+#[test]
+fn test_beast_single_frame() -> Result<(), Box<dyn Error>> {
+    let frame: Vec<u8> = vec![
+        0x1a, b'2', 0x00, 0x00, 0x00, 0x1a, 0x1a, 0x00, 0x00, 0x55, 0x5d, 0x48, 0x40, 0xd6, 0xf8,
+        0x74, 0x0f,
+    ];
+    let message: BeastMessage = frame.to_beast()?;
+    assert_eq!(message.timestamp, 0x1a0000);
+    assert_eq!(message.data.len(), 7);
+    match frame.decode_message()? {
+        ADSBMessage::Beast(decoded) => assert_eq!(decoded, message),
+        other => panic!("Expected a Beast message, got {:?}", other),
+    }
+    assert!(frame[..frame.len() - 1].to_beast().is_err());
+    assert!(vec![0x1a, b'9', 0x00].to_beast().is_err());
+    assert!((message.signal_level_dbfs() - -9.54).abs() < 0.01);
+    Ok(())
+}
+
+/// This test decodes every frame of the captured Beast stream, encodes it again with `to_beast_bytes()`,
+/// and validates that the re-encoded stream is byte for byte identical to the original frames.
+#[test]
+fn test_beast_encoding() -> Result<(), Box<dyn Error>> {
+    let buffer: Vec<u8> = fs::read("test_files/beast_01.bin")?;
+    let (messages, consumed) = decode_beast_frames(&buffer);
+    let mut encoded: Vec<u8> = Vec::new();
+    for message in &messages {
+        encoded.extend(message.to_beast_bytes()?);
+    }
+    assert_eq!(encoded.as_slice(), &buffer[3..consumed]);
+    let (decoded, _) = decode_beast_frames(&encoded);
+    assert_eq!(decoded, messages);
+    let mut bad_length: BeastMessage = messages[0].clone();
+    bad_length.message_type = BeastMessageType::ModeSShort;
+    assert!(bad_length.to_beast_bytes().is_err());
+    let mut bad_timestamp: BeastMessage = messages[0].clone();
+    bad_timestamp.timestamp = 1 << 48;
+    assert!(bad_timestamp.to_beast_bytes().is_err());
+    Ok(())
+}
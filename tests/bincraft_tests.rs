@@ -0,0 +1,81 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use adsb_parser::bincraft::NewBinCraftMessages;
+use std::error::Error;
+use std::fs;
+
+/// This test decodes a binCraft buffer and validates the scaled fields of each record,
+/// keeping the aircraft without a position.
+#[test]
+fn test_bincraft_parsing() -> Result<(), Box<dyn Error>> {
+    let buffer: Vec<u8> = fs::read("test_files/bincraft_01.bin")?;
+    let aircraft: Vec<ADSBJsonMessage> = buffer.to_bincraft()?;
+    assert_eq!(aircraft.len(), 4);
+    assert!(aircraft.iter().all(|message| message.now == 1663700000.123));
+
+    let airborne = &aircraft[0];
+    assert_eq!(airborne.hex, "4840d6");
+    assert_eq!(airborne.adsb_type, "adsb_icao");
+    assert_eq!(airborne.flight.as_deref(), Some("BAW123  "));
+    assert_eq!(airborne.aircraft_registration, "G-EUUA");
+    assert_eq!(airborne.aircraft_type.as_deref(), Some("A320"));
+    assert!(matches!(airborne.alt_baro, Some(Altitude::I32(38000))));
+    assert_eq!(airborne.alt_geom, Some(39000));
+    assert_eq!(airborne.baro_rate, Some(-960));
+    assert_eq!(airborne.gs, Some(432.1));
+    assert_eq!(airborne.mach, Some(0.782));
+    assert_eq!(airborne.track, Some(271.0));
+    assert_eq!(airborne.roll, Some(-2.5));
+    assert_eq!(airborne.squawk.as_deref(), Some("7421"));
+    assert_eq!(airborne.category.as_deref(), Some("A3"));
+    assert_eq!(airborne.nav_qnh, Some(1013.2));
+    assert_eq!(airborne.nav_altitude_mcp, Some(36000));
+    assert!(matches!(
+        airborne.nav_modes.as_deref(),
+        Some([NavModes::AutoPilot, NavModes::VNAV, NavModes::LNAV])
+    ));
+    assert_eq!(airborne.oat, Some(-52));
+    assert!(matches!(airborne.sil_type, SilType::PerHour));
+    assert_eq!(airborne.nac_p, Some(9));
+    assert_eq!(airborne.gva, Some(2));
+    assert_eq!(airborne.nic_baro, Some(1));
+    assert_eq!(airborne.version, Some(2));
+    assert_eq!(airborne.seen_pos, Some(0.3));
+    assert_eq!(airborne.messages, 5432);
+    assert!((airborne.lat.unwrap_or_default() - 51.4567).abs() < 1e-5);
+    assert!((airborne.lon.unwrap_or_default() + 0.123456).abs() < 1e-5);
+    assert!((airborne.rssi + 2.1).abs() < 0.1);
+
+    let no_position = &aircraft[1];
+    assert_eq!(no_position.hex, "40621d");
+    assert_eq!(no_position.position(), None);
+    assert_eq!(no_position.seen_pos, None);
+
+    let ground = &aircraft[2];
+    assert_eq!(ground.hex, "ae1234");
+    assert!(matches!(ground.alt_baro, Some(Altitude::Ground(_))));
+    assert_eq!(ground.gs, Some(12.5));
+    assert_eq!(ground.track, Some(45.0));
+    assert_eq!(ground.alt_geom, None);
+    assert_eq!(ground.mach, None);
+    assert_eq!(ground.dbflags, Some(1));
+    assert_eq!(ground.aircraft_registration, "");
+
+    let mlat = &aircraft[3];
+    assert_eq!(mlat.hex, "~0abcde");
+    assert_eq!(mlat.adsb_type, "mlat");
+    assert_eq!(mlat.squawk.as_deref(), Some("7700"));
+    assert_eq!(mlat.emergency.as_deref(), Some("general"));
+    assert_eq!(mlat.flight, None);
+    assert_eq!(mlat.aircraft_type, None);
+    Ok(())
+}
+
+/// This test checks that buffers which do not divide into whole records are rejected.
+#[test]
+fn test_bincraft_invalid_buffer() -> Result<(), Box<dyn Error>> {
+    let mut buffer: Vec<u8> = fs::read("test_files/bincraft_01.bin")?;
+    buffer.truncate(buffer.len() - 1);
+    assert!(buffer.to_bincraft().is_err());
+    assert!(buffer[..8].to_bincraft().is_err());
+    Ok(())
+}
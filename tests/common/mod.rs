@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use adsb_parser::adsb_json::NewADSBJsonMessage;
 use adsb_parser::ADSBMessage;
 use byte_unit::Byte;
@@ -22,6 +24,10 @@ use thousands::Separable;
 /// Enum for indicating test data type.
 pub enum MessageType {
     ADSBJson,
+    Avr,
+    Sbs,
+    Uat,
+    UatRaw,
     All,
 }
 
@@ -389,7 +395,19 @@ pub fn combine_files_of_message_type(
 ) -> Result<Vec<String>, Box<dyn Error>> {
     match message_type {
         MessageType::ADSBJson => combine_found_files(glob("test_files/adsb_*.json")),
-        MessageType::All => combine_found_files(glob("test_files/adsb*")),
+        MessageType::Avr => combine_found_files(glob("test_files/avr_*.txt")),
+        MessageType::Sbs => combine_found_files(glob("test_files/sbs_*.txt")),
+        MessageType::Uat => combine_found_files(glob("test_files/uat_*.json")),
+        MessageType::UatRaw => combine_found_files(glob("test_files/uat_raw_*.txt")),
+        MessageType::All => {
+            let mut all_messages: Vec<String> =
+                combine_files_of_message_type(MessageType::ADSBJson)?;
+            all_messages.extend(combine_files_of_message_type(MessageType::Avr)?);
+            all_messages.extend(combine_files_of_message_type(MessageType::Sbs)?);
+            all_messages.extend(combine_files_of_message_type(MessageType::Uat)?);
+            all_messages.extend(combine_files_of_message_type(MessageType::UatRaw)?);
+            Ok(all_messages)
+        }
     }
 }
 
@@ -399,6 +417,10 @@ pub fn load_files_of_message_type(
 ) -> Result<Vec<TestFile>, Box<dyn Error>> {
     match message_type {
         MessageType::ADSBJson => load_found_files(glob("test_files/adsb_*.json")),
+        MessageType::Avr => load_found_files(glob("test_files/avr_*.txt")),
+        MessageType::Sbs => load_found_files(glob("test_files/sbs_*.txt")),
+        MessageType::Uat => load_found_files(glob("test_files/uat_*.json")),
+        MessageType::UatRaw => load_found_files(glob("test_files/uat_raw_*.txt")),
         MessageType::All => load_found_files(glob("test_files/adsb.*")),
     }
 }
@@ -464,7 +486,7 @@ pub fn compare_errors(
     match (error_1, error_2) {
         (None, Ok(_)) => {}
         (Some(library_error), Ok(value_data)) => {
-            assert!(false, "Library {}, Value {:?}", &library_error, &value_data)
+            panic!("Library {}, Value {:?}", &library_error, &value_data)
         }
         (Some(library_error), Err(value_error)) => assert_eq!(
             library_error.to_string(),
@@ -472,10 +494,10 @@ pub fn compare_errors(
             "Errors processing {} do not match between library {} and serde Value {}",
             line,
             library_error,
-            value_error.to_string()
+            value_error
         ),
         (None, Err(value_error)) => {
-            assert!(false, "Library passed, but Value is {:?}", &value_error)
+            panic!("Library passed, but Value is {:?}", &value_error)
         }
     }
 }
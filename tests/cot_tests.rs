@@ -0,0 +1,75 @@
+use adsb_parser::adsb_json::ADSBJsonMessage;
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::cot::{CotEvent, COT_UNKNOWN};
+use std::error::Error;
+use std::fs;
+
+/// This test converts an `aircraft.json` snapshot to CoT events,
+/// validating the event times, point errors and the XML written for TAK clients.
+#[test]
+fn test_cot_from_adsb_json() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let events: Vec<CotEvent> = snapshot
+        .aircraft
+        .iter()
+        .filter_map(CotEvent::from_adsb_json)
+        .collect();
+    assert!(!events.is_empty());
+
+    let airliner: &CotEvent = &events[0];
+    assert_eq!(airliner.uid, "ICAO-A05DBC");
+    assert_eq!(airliner.event_type, "a-n-A-C-F");
+    assert_eq!(airliner.how, "m-g");
+    assert_eq!(airliner.callsign, "UAL488");
+    assert_eq!(airliner.ce, 30.0);
+    assert_eq!(airliner.le, 45.0);
+    assert!((airliner.hae - 10401.3).abs() < 0.01);
+    assert_eq!(
+        airliner.remarks.as_deref(),
+        Some("Registration N12218, type B738, squawk 0636")
+    );
+
+    let xml: String = airliner.to_cot_string();
+    assert!(xml.starts_with(
+        "<event version=\"2.0\" uid=\"ICAO-A05DBC\" type=\"a-n-A-C-F\" how=\"m-g\" \
+time=\"2023-02-08T15:16:09.700Z\" start=\"2023-02-08T15:16:09.700Z\" stale=\"2023-02-08T15:17:09.700Z\">"
+    ));
+    assert!(xml.contains(
+        "<point lat=\"37.440521\" lon=\"-106.502419\" hae=\"10401.3\" ce=\"30.0\" le=\"45.0\"/>"
+    ));
+    assert!(xml.contains("<contact callsign=\"UAL488\"/>"));
+    assert!(xml.contains("<track course=\"224.82\" speed=\"230.63\"/>"));
+    assert!(xml.ends_with("</detail></event>"));
+    assert!(airliner.to_cot_string_newline().ends_with("</event>\n"));
+    Ok(())
+}
+
+/// This test validates the MIL-STD-2525 type chosen from `category` and `dbflags`,
+/// the unknown errors and that aircraft without a position are not converted.
+#[test]
+fn test_cot_event_type() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let mut helicopter: ADSBJsonMessage = snapshot.aircraft[0].clone();
+    helicopter.category = Some("A7".to_string());
+    helicopter.dbflags = Some(1);
+    helicopter.nac_p = None;
+    helicopter.gva = Some(0);
+    helicopter.flight = Some("A&B <1>".to_string());
+    let event: CotEvent = CotEvent::from_adsb_json(&helicopter).ok_or("Missing event")?;
+    assert_eq!(event.event_type, "a-n-A-M-H");
+    assert_eq!(event.ce, COT_UNKNOWN);
+    assert_eq!(event.le, COT_UNKNOWN);
+    assert!(event
+        .to_cot_string()
+        .contains("<contact callsign=\"A&amp;B &lt;1&gt;\"/>"));
+
+    helicopter.dbflags = Some(0);
+    let event: CotEvent = CotEvent::from_adsb_json(&helicopter).ok_or("Missing event")?;
+    assert_eq!(event.event_type, "a-n-A-C-H");
+
+    helicopter.lat = None;
+    assert!(CotEvent::from_adsb_json(&helicopter).is_none());
+    Ok(())
+}
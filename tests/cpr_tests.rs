@@ -0,0 +1,99 @@
+use adsb_parser::adsb_json::ADSBJsonMessage;
+use adsb_parser::avr::NewAvrMessage;
+use adsb_parser::cpr::{cpr_nl, decode_global, decode_local, CprDecoder, CprFrame};
+use adsb_parser::modes::{ModeSMessage, NewModeSMessage};
+use std::error::Error;
+
+fn frame(line: &str, timestamp: f64) -> Result<CprFrame, Box<dyn Error>> {
+    let message: ModeSMessage = line.to_avr()?.to_modes()?;
+    Ok(CprFrame::from_modes(&message, timestamp).ok_or("not a position frame")?)
+}
+
+/// This test decodes airborne and surface CPR frames globally and locally,
+/// validating the positions, the choice of the most recent frame and the longitude zone checks.
+#[test]
+fn test_cpr_decode() -> Result<(), Box<dyn Error>> {
+    assert_eq!(cpr_nl(0.0), 59);
+    assert_eq!(cpr_nl(52.2572), 36);
+    assert_eq!(cpr_nl(-87.0), 2);
+    assert_eq!(cpr_nl(89.0), 1);
+
+    let even: CprFrame = frame("*8D40621D58C382D690C8AC2863A7;", 1.0)?;
+    let odd: CprFrame = frame("*8D40621D58C386435CC412692AD6;", 0.0)?;
+    let (lat, lon) = decode_global(&even, &odd, None).ok_or("no global position")?;
+    assert!((lat - 52.25720).abs() < 1e-5);
+    assert!((lon - 3.91937).abs() < 1e-5);
+
+    let later_odd: CprFrame = CprFrame {
+        timestamp: 2.0,
+        ..odd
+    };
+    let (lat, lon) = decode_global(&even, &later_odd, None).ok_or("no global position")?;
+    assert!((lat - 52.26578).abs() < 1e-5);
+    assert!((lon - 3.93891).abs() < 1e-5);
+
+    let (lat, lon) = decode_local(&even, (52.258, 3.918)).ok_or("no local position")?;
+    assert!((lat - 52.25720).abs() < 1e-5);
+    assert!((lon - 3.91937).abs() < 1e-5);
+
+    // Frames near a zone boundary with different zone counts cannot be paired.
+    let straddling: CprFrame = CprFrame { lat_cpr: 0, ..odd };
+    assert_eq!(decode_global(&even, &straddling, None), None);
+
+    let surface_even: CprFrame = frame("*8C4841753AAB238733C8CD4020B1;", 1.0)?;
+    let surface_odd: CprFrame = frame("*8C4841753A8A35323FAEBDAC702D;", 0.0)?;
+    assert_eq!(decode_global(&surface_even, &surface_odd, None), None);
+    let (lat, lon) = decode_global(&surface_even, &surface_odd, Some((51.990, 4.375)))
+        .ok_or("no surface position")?;
+    assert!((lat - 52.32304).abs() < 1e-5);
+    assert!((lon - 4.73047).abs() < 1e-5);
+    let (lat, lon) = decode_local(&surface_even, (51.990, 4.375)).ok_or("no local position")?;
+    assert!((lat - 52.32304).abs() < 1e-5);
+    assert!((lon - 4.73047).abs() < 1e-5);
+    Ok(())
+}
+
+/// This test feeds position frames through a `CprDecoder`,
+/// validating that a pair is needed first, that later frames decode locally, and that `ADSBJsonMessage` gets the position.
+#[test]
+fn test_cpr_decoder() -> Result<(), Box<dyn Error>> {
+    let mut decoder: CprDecoder = CprDecoder::new(Some((51.990, 4.375)));
+    let odd: ModeSMessage = "*8D40621D58C386435CC412692AD6;".to_avr()?.to_modes()?;
+    let even: ModeSMessage = "*8D40621D58C382D690C8AC2863A7;".to_avr()?.to_modes()?;
+
+    let first: ADSBJsonMessage = decoder.to_adsb_json(&odd, 1675869370.0);
+    assert_eq!(first.seen_pos, None);
+
+    let paired: ADSBJsonMessage = decoder.to_adsb_json(&even, 1675869371.0);
+    assert_eq!(paired.seen_pos, Some(0.0));
+    assert!((paired.lat.unwrap_or_default() - 52.2572).abs() < 1e-4);
+    assert!((paired.lon.unwrap_or_default() - 3.91937).abs() < 1e-4);
+
+    // Long after the pair expires, a single frame still decodes against the last position.
+    let (lat, lon) = decoder
+        .decode_message(&odd, 1675869400.0)
+        .ok_or("no local position")?;
+    assert!((lat - 52.26578).abs() < 1e-5);
+    assert!((lon - 3.93891).abs() < 1e-5);
+
+    decoder.expire(1675870100.0);
+    assert_eq!(decoder.decode_message(&even, 1675870101.0), None);
+    Ok(())
+}
+
+/// This test validates that a single surface frame decodes locally against the receiver location.
+#[test]
+fn test_cpr_decoder_surface() -> Result<(), Box<dyn Error>> {
+    let surface: ModeSMessage = "*8C4841753AAB238733C8CD4020B1;".to_avr()?.to_modes()?;
+    assert_eq!(
+        CprDecoder::new(None).decode_message(&surface, 1675869370.0),
+        None
+    );
+    let mut decoder: CprDecoder = CprDecoder::new(Some((51.990, 4.375)));
+    let (lat, lon) = decoder
+        .decode_message(&surface, 1675869370.0)
+        .ok_or("no surface position")?;
+    assert!((lat - 52.32304).abs() < 1e-5);
+    assert!((lon - 4.73047).abs() < 1e-5);
+    Ok(())
+}
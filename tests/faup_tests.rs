@@ -0,0 +1,81 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::faup::{FaupMessage, NewFaupMessage};
+use std::error::Error;
+use std::fs;
+
+/// This test reads saved faup1090 lines and converts them to `ADSBJsonMessage`,
+/// validating the Tcl list values, non-ICAO addresses, ground reports and reports without a position.
+#[test]
+fn test_faup_decode() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/faup_01.txt")?;
+    let messages: Vec<FaupMessage> = contents
+        .lines()
+        .map(|line| line.to_faup())
+        .collect::<Result<Vec<FaupMessage>, _>>()?;
+    assert_eq!(messages.len(), 4);
+
+    let airliner: ADSBJsonMessage = messages[0].to_adsb_json()?;
+    assert_eq!(airliner.now, 1675869370.0);
+    assert_eq!(airliner.hex, "a05dbc");
+    assert_eq!(airliner.flight.as_deref(), Some("UAL488"));
+    assert!(matches!(airliner.alt_baro, Some(Altitude::I32(34000))));
+    assert_eq!(airliner.lat, Some(37.44052));
+    assert_eq!(airliner.nic, Some(8));
+    assert_eq!(airliner.rc, Some(186));
+    assert_eq!(airliner.gs, Some(448.3));
+    assert_eq!(airliner.track, Some(224.82));
+    assert_eq!(airliner.nav_qnh, Some(1013.6));
+    assert_eq!(
+        airliner.nav_modes,
+        Some(vec![NavModes::AutoPilot, NavModes::VNAV, NavModes::TCAS])
+    );
+    assert_eq!(airliner.sil_type, SilType::PerHour);
+    assert_eq!(airliner.spi, Some(0));
+
+    let tisb: ADSBJsonMessage = messages[1].to_adsb_json()?;
+    assert_eq!(tisb.hex, "~2a4f1c");
+    assert_eq!(tisb.adsb_type, "tisb_other");
+
+    let ground: ADSBJsonMessage = messages[2].to_adsb_json()?;
+    assert!(matches!(ground.alt_baro, Some(Altitude::Ground(_))));
+    assert_eq!(messages[2].air_ground.as_deref(), Some("G+"));
+    let uncertain: FaupMessage = "clock\t1675869372\thexid\tAB2C4D\tairGround\tG".to_faup()?;
+    assert!(matches!(
+        uncertain.to_adsb_json()?.alt_baro,
+        Some(Altitude::Ground(_))
+    ));
+
+    assert_eq!(messages[3].to_adsb_json()?.position(), None);
+    assert!("hexid\tA05DBC\talt\t34000".to_faup().is_err());
+    assert!("clock\t1675869370\thexid".to_faup().is_err());
+    Ok(())
+}
+
+/// This test converts an `aircraft.json` snapshot to faup1090 lines and parses them back,
+/// validating that the round trip keeps the state of each aircraft.
+#[test]
+fn test_faup_round_trip() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    for original in &snapshot.aircraft {
+        let line: String = FaupMessage::from_adsb_json(original).to_faup_string_newline();
+        assert!(line.ends_with('\n'));
+        let message: FaupMessage = line.to_faup()?;
+        assert_eq!(message, FaupMessage::from_adsb_json(original));
+        assert_eq!(message.clock, (original.now - original.seen) as u64);
+
+        let decoded: ADSBJsonMessage = message.to_adsb_json()?;
+        assert_eq!(decoded.hex, original.hex);
+        assert_eq!(
+            decoded.flight.as_deref(),
+            original.flight.as_deref().map(str::trim)
+        );
+        assert_eq!(decoded.squawk, original.squawk);
+        assert_eq!(decoded.baro_rate, original.baro_rate);
+        assert_eq!(decoded.nav_modes, original.nav_modes);
+        assert!((decoded.lat.unwrap_or_default() - original.lat.unwrap_or_default()).abs() < 1e-5);
+        assert!((decoded.lon.unwrap_or_default() - original.lon.unwrap_or_default()).abs() < 1e-5);
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use adsb_parser::fisb::{
+    FisbApdu, FisbInfoFrame, FisbProduct, FisbTime, NewUatUplinkMessage, NexradBins,
+    UatUplinkMessage,
+};
+use std::error::Error;
+use std::fs;
+
+/// Returns the APDU of the information frame at `index`, failing the test for other frame types.
+fn apdu(message: &UatUplinkMessage, index: usize) -> &FisbApdu {
+    match &message.info_frames[index] {
+        FisbInfoFrame::Apdu(apdu) => apdu,
+        other => panic!("Expected an APDU, got {:?}", other),
+    }
+}
+
+/// This test decodes an uplink carrying a DLAC text product and validates the header, product time and records.
+#[test]
+fn test_fisb_text_product() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/fisb_01.txt")?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let message: UatUplinkMessage = lines[0].to_fisb()?;
+    let position = message.position.as_ref().ok_or("Missing position")?;
+    assert!((position.lat - 35.0438).abs() < 1e-4);
+    assert!((position.lon + 106.6165).abs() < 1e-4);
+    assert!(message.utc_coupled);
+    assert_eq!(message.slot_id, 5);
+    assert_eq!(message.tisb_site_id, 7);
+    assert_eq!(
+        message
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.errors),
+        Some(3)
+    );
+    assert_eq!(message.info_frames.len(), 2);
+    assert!(matches!(
+        message.info_frames[1],
+        FisbInfoFrame::Other { frame_type: 14, .. }
+    ));
+
+    let text = apdu(&message, 0);
+    assert_eq!(text.product_id, 413);
+    assert_eq!(
+        text.time,
+        FisbTime {
+            month: Some(2),
+            day: Some(5),
+            hours: 19,
+            minutes: 55,
+            seconds: None,
+        }
+    );
+    let records = match &text.product {
+        FisbProduct::Text(records) => records,
+        other => panic!("Expected a text product, got {:?}", other),
+    };
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].record_type, "METAR");
+    assert_eq!(records[0].location, "KABQ");
+    assert_eq!(
+        records[0].text,
+        "METAR KABQ 051952Z 27011KT 10SM FEW080 SCT200 12/M07 A3002 RMK AO2 SLP148="
+    );
+    assert_eq!(records[1].record_type, "TAF");
+    assert_eq!(
+        records[1].text,
+        "TAF KABQ 051720Z 0518/0618\n    28012G20KT P6SM SCT080 BKN200="
+    );
+    Ok(())
+}
+
+/// This test decodes an uplink carrying NEXRAD blocks, both run length encoded and empty, and a segmented product.
+#[test]
+fn test_fisb_nexrad_product() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/fisb_01.txt")?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let message: UatUplinkMessage = lines[1].to_fisb()?;
+    assert_eq!(message.slot_id, 12);
+    assert_eq!(message.info_frames.len(), 3);
+
+    let conus = apdu(&message, 0);
+    assert_eq!(conus.product_id, 64);
+    assert_eq!((conus.time.hours, conus.time.minutes), (19, 50));
+    let block = match &conus.product {
+        FisbProduct::Nexrad(block) => block,
+        other => panic!("Expected a NEXRAD product, got {:?}", other),
+    };
+    assert_eq!(block.block_number, 123456);
+    assert_eq!(block.scale_factor, 1);
+    let intensities = match &block.bins {
+        NexradBins::Intensities(intensities) => intensities,
+        other => panic!("Expected NEXRAD intensities, got {:?}", other),
+    };
+    assert_eq!(intensities.len(), 128);
+    assert_eq!(intensities[0], 0);
+    assert_eq!(intensities[32], 2);
+    assert_eq!(intensities[64], 5);
+    assert_eq!(intensities[80], 7);
+    assert_eq!(intensities[127], 0);
+
+    let regional = apdu(&message, 1);
+    assert_eq!(regional.time.seconds, Some(30));
+    match &regional.product {
+        FisbProduct::Nexrad(block) => assert_eq!(
+            block.bins,
+            NexradBins::Empty(vec![200000, 200001, 200002, 200005])
+        ),
+        other => panic!("Expected a NEXRAD product, got {:?}", other),
+    }
+
+    let segmented = apdu(&message, 2);
+    assert!(segmented.segmented);
+    assert_eq!(segmented.time.seconds, Some(12));
+    assert_eq!(
+        segmented.product,
+        FisbProduct::Unsupported(vec![0xaa, 0xbb])
+    );
+
+    assert!("+0102;".to_fisb().is_err());
+    assert!("-0102;".to_fisb().is_err());
+    Ok(())
+}
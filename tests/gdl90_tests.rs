@@ -0,0 +1,120 @@
+use adsb_parser::adsb_json::ADSBJsonMessage;
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::gdl90::{
+    gdl90_crc, gdl90_frame, Gdl90Heartbeat, Gdl90OwnshipGeometricAltitude, Gdl90TrafficReport,
+};
+use std::error::Error;
+use std::fs;
+
+/// This test encodes the heartbeat and traffic report examples from the GDL90 specification,
+/// validating the CRC, the field packing and the byte stuffing of the frame.
+#[test]
+fn test_gdl90_specification_examples() {
+    let heartbeat: Gdl90Heartbeat = Gdl90Heartbeat {
+        gps_position_valid: true,
+        uat_initialized: true,
+        csa_requested: true,
+        utc_ok: true,
+        timestamp: 0xd0db,
+        uplink_count: 1,
+        basic_long_count: 2,
+        ..Default::default()
+    };
+    assert_eq!(
+        gdl90_crc(&[0x00, 0x81, 0x41, 0xdb, 0xd0, 0x08, 0x02]),
+        0x8bb3
+    );
+    assert_eq!(
+        heartbeat.to_bytes(),
+        vec![0x7e, 0x00, 0x81, 0x41, 0xdb, 0xd0, 0x08, 0x02, 0xb3, 0x8b, 0x7e]
+    );
+
+    let traffic: Gdl90TrafficReport = Gdl90TrafficReport {
+        address: 0xab4549,
+        lat: 44.90708,
+        lon: -122.99488,
+        altitude: Some(5000),
+        airborne: true,
+        track_type: 1,
+        nic: 10,
+        nac_p: 9,
+        horizontal_velocity: Some(123),
+        vertical_velocity: Some(64),
+        track: Some(45.0),
+        emitter_category: 1,
+        callsign: "N825V".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        traffic.to_payload(),
+        vec![
+            0x00, 0xab, 0x45, 0x49, 0x1f, 0xef, 0x15, 0xa8, 0x89, 0x78, 0x0f, 0x09, 0xa9, 0x07,
+            0xb0, 0x01, 0x20, 0x01, 0x4e, 0x38, 0x32, 0x35, 0x56, 0x20, 0x20, 0x20, 0x00
+        ]
+    );
+
+    assert_eq!(
+        gdl90_frame(0x7e, &[0x7d]),
+        vec![0x7e, 0x7d, 0x5e, 0x7d, 0x5d, 0x7d, 0x5d, 0x7d, 0x5e, 0x7e]
+    );
+}
+
+/// This test converts an `aircraft.json` snapshot to GDL90 traffic reports,
+/// validating the conversion from `ADSBJsonMessage` and that every frame is correctly delimited.
+#[test]
+fn test_gdl90_from_adsb_json() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let reports: Vec<Gdl90TrafficReport> = snapshot
+        .aircraft
+        .iter()
+        .filter_map(Gdl90TrafficReport::from_adsb_json)
+        .collect();
+    assert!(!reports.is_empty());
+
+    let airliner: &Gdl90TrafficReport = &reports[0];
+    assert_eq!(airliner.address, 0xa05dbc);
+    assert_eq!(airliner.address_type, 0);
+    assert_eq!(airliner.altitude, Some(34000));
+    assert!(airliner.airborne);
+    assert_eq!(airliner.nic, 8);
+    assert_eq!(airliner.nac_p, 9);
+    assert_eq!(airliner.horizontal_velocity, Some(448));
+    assert_eq!(airliner.vertical_velocity, Some(0));
+    assert_eq!(airliner.track_type, 1);
+    assert_eq!(airliner.emitter_category, 3);
+    assert_eq!(airliner.callsign, "UAL488");
+
+    let payload: Vec<u8> = airliner.to_payload();
+    assert_eq!(payload.len(), 27);
+    assert_eq!(&payload[10..12], &[0x57, 0x89]);
+    assert_eq!(&payload[18..26], b"UAL488  ");
+
+    let mut no_altitude: ADSBJsonMessage = snapshot.aircraft[0].clone();
+    no_altitude.alt_baro = None;
+    let report: Gdl90TrafficReport =
+        Gdl90TrafficReport::from_adsb_json(&no_altitude).ok_or("Missing report")?;
+    assert_eq!(report.altitude, None);
+    assert_eq!(&report.to_payload()[10..12], &[0xff, 0xf9]);
+
+    for report in &reports {
+        for frame in [report.to_bytes(), report.to_ownship_bytes()] {
+            assert_eq!(frame.first(), Some(&0x7e));
+            assert_eq!(frame.last(), Some(&0x7e));
+            assert!(!frame[1..frame.len() - 1].contains(&0x7e));
+        }
+    }
+
+    let geometric_altitude: Gdl90OwnshipGeometricAltitude =
+        Gdl90OwnshipGeometricAltitude::from_adsb_json(&snapshot.aircraft[0])
+            .ok_or("Missing alt_geom")?;
+    assert_eq!(
+        geometric_altitude.to_payload(),
+        vec![0x1a, 0xa9, 0x00, 0x2d]
+    );
+    assert_eq!(
+        Gdl90Heartbeat::from_unix_timestamp(snapshot.now).timestamp,
+        54970
+    );
+    Ok(())
+}
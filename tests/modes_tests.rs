@@ -0,0 +1,437 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude, NavModes, SilType};
+use adsb_parser::avr::NewAvrMessage;
+use adsb_parser::beast::BeastMessage;
+use adsb_parser::modes::comm_b::CommB;
+use adsb_parser::modes::crc::{find_bit_errors, modes_syndrome, ErrorCorrection, FrameQuality};
+use adsb_parser::modes::extended_squitter::{
+    AirbornePosition, AirborneVelocity, ExtendedSquitter, Identification,
+};
+use adsb_parser::modes::gillham::{decode_ac12, decode_ac13, decode_id13};
+use adsb_parser::modes::{ModeSMessage, NewModeSMessage};
+use std::error::Error;
+use std::fs;
+
+/// This test decodes saved AVR lines into Mode-S frames,
+/// validating the downlink format, address, type code and each typed ME payload.
+#[test]
+fn test_modes_extended_squitter_decode() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/modes_01.txt")?;
+    let frames: Vec<BeastMessage> = contents
+        .lines()
+        .map(|line| line.to_avr())
+        .collect::<Result<Vec<BeastMessage>, _>>()?;
+    let messages: Vec<ModeSMessage> = frames
+        .iter()
+        .map(|frame| frame.to_modes())
+        .collect::<Result<Vec<ModeSMessage>, _>>()?;
+    assert_eq!(messages.len(), 11);
+
+    assert_eq!(messages[0].downlink_format, 17);
+    assert_eq!(messages[0].capability, Some(5));
+    assert_eq!(messages[0].address, 0x4840d6);
+    assert_eq!(messages[0].type_code, Some(4));
+    assert_eq!(
+        messages[0].extended_squitter,
+        Some(ExtendedSquitter::Identification(Identification {
+            category: "A0".to_string(),
+            callsign: "KLM1023 ".to_string(),
+        }))
+    );
+
+    assert_eq!(
+        messages[1].extended_squitter,
+        Some(ExtendedSquitter::AirbornePosition(AirbornePosition {
+            surveillance_status: 0,
+            nic_supplement_b: false,
+            altitude: Some(38000),
+            gnss_height: false,
+            utc_synchronised: false,
+            odd: false,
+            lat_cpr: 93000,
+            lon_cpr: 51372,
+        }))
+    );
+    match &messages[2].extended_squitter {
+        Some(ExtendedSquitter::AirbornePosition(position)) => {
+            assert!(position.odd);
+            assert_eq!((position.lat_cpr, position.lon_cpr), (74158, 50194));
+        }
+        other => panic!("Expected an airborne position, got {:?}", other),
+    }
+
+    match &messages[3].extended_squitter {
+        Some(ExtendedSquitter::AirborneVelocity(velocity)) => {
+            assert_eq!(velocity.subtype, 1);
+            assert!((velocity.ground_speed.unwrap_or_default() - 159.20).abs() < 0.01);
+            assert!((velocity.track.unwrap_or_default() - 182.88).abs() < 0.01);
+            assert_eq!(velocity.vertical_rate, Some(-832));
+            assert!(!velocity.vertical_rate_barometric);
+            assert_eq!(velocity.geometric_minus_barometric, Some(550));
+        }
+        other => panic!("Expected an airborne velocity, got {:?}", other),
+    }
+    assert_eq!(
+        messages[4].extended_squitter,
+        Some(ExtendedSquitter::AirborneVelocity(AirborneVelocity {
+            subtype: 3,
+            intent_change: false,
+            nac_v: 0,
+            ground_speed: None,
+            track: None,
+            heading: Some(243.98438),
+            airspeed: Some(375),
+            true_airspeed: true,
+            vertical_rate: Some(-2304),
+            vertical_rate_barometric: true,
+            geometric_minus_barometric: None,
+        }))
+    );
+
+    assert_eq!(messages[5].capability, Some(4));
+    match &messages[5].extended_squitter {
+        Some(ExtendedSquitter::SurfacePosition(position)) => {
+            assert_eq!(position.ground_speed, Some(18.0));
+            assert_eq!(position.track, Some(140.625));
+            assert_eq!((position.lat_cpr, position.lon_cpr), (115609, 116941));
+        }
+        other => panic!("Expected a surface position, got {:?}", other),
+    }
+
+    match &messages[6].extended_squitter {
+        Some(ExtendedSquitter::AircraftStatus(status)) => {
+            assert_eq!(status.emergency, "unlawful");
+            assert_eq!(status.squawk, "7500");
+        }
+        other => panic!("Expected an aircraft status, got {:?}", other),
+    }
+
+    match &messages[7].extended_squitter {
+        Some(ExtendedSquitter::TargetState(state)) => {
+            assert_eq!(state.selected_altitude, Some(16992));
+            assert!(!state.selected_altitude_fms);
+            assert!((state.baro_setting.unwrap_or_default() - 1012.8).abs() < 0.01);
+            assert!((state.selected_heading.unwrap_or_default() - 66.8).abs() < 0.01);
+            assert_eq!((state.nac_p, state.nic_baro, state.sil), (9, 1, 3));
+            assert_eq!(
+                state.nav_modes,
+                Some(vec![
+                    NavModes::AutoPilot,
+                    NavModes::VNAV,
+                    NavModes::TCAS,
+                    NavModes::LNAV
+                ])
+            );
+        }
+        other => panic!("Expected a target state, got {:?}", other),
+    }
+
+    match &messages[8].extended_squitter {
+        Some(ExtendedSquitter::OperationalStatus(status)) => {
+            assert!(!status.surface);
+            assert_eq!(status.version, 2);
+            assert_eq!((status.nac_p, status.sil), (10, 3));
+            assert_eq!(status.gva, Some(2));
+            assert_eq!(status.sda, Some(2));
+            assert_eq!(status.nic_baro, Some(1));
+            assert!(status.tcas_operational);
+        }
+        other => panic!("Expected an operational status, got {:?}", other),
+    }
+
+    assert_eq!(messages[9].downlink_format, 18);
+    assert_eq!(messages[9].control_field, Some(1));
+    assert!(messages[9].is_non_icao());
+    assert_eq!(messages[9].hex(), "~2a4f1c");
+    assert_eq!(messages[9].address_type(), "adsb_other");
+
+    assert_eq!(messages[10].downlink_format, 11);
+    assert_eq!(messages[10].address, 0x4840d6);
+    assert_eq!(messages[10].extended_squitter, None);
+    Ok(())
+}
+
+/// This test converts decoded frames to `ADSBJsonMessage`,
+/// validating that each payload fills the same fields as a readsb aircraft record and that unsupported frames are rejected.
+#[test]
+fn test_modes_to_adsb_json() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/modes_01.txt")?;
+    let frames: Vec<BeastMessage> = contents
+        .lines()
+        .map(|line| line.to_avr())
+        .collect::<Result<Vec<BeastMessage>, _>>()?;
+    let now: f64 = 1675869370.0;
+    let messages: Vec<ADSBJsonMessage> = frames
+        .iter()
+        .map(|frame| frame.to_adsb_json(now))
+        .collect::<Result<Vec<ADSBJsonMessage>, _>>()?;
+
+    assert_eq!(messages[0].now, now);
+    assert_eq!(messages[0].hex, "4840d6");
+    assert_eq!(messages[0].adsb_type, "adsb_icao");
+    assert_eq!(messages[0].flight.as_deref(), Some("KLM1023 "));
+    assert_eq!(messages[0].category.as_deref(), Some("A0"));
+
+    assert!(matches!(messages[1].alt_baro, Some(Altitude::I32(38000))));
+    assert_eq!((messages[1].nic, messages[1].rc), (Some(8), Some(186)));
+    assert_eq!(messages[1].seen_pos, None);
+
+    assert_eq!(messages[3].geom_rate, Some(-832));
+    assert_eq!(messages[3].nac_v, Some(0));
+    assert_eq!(messages[4].tas, Some(375));
+    assert_eq!(messages[4].baro_rate, Some(-2304));
+    assert_eq!(messages[4].mag_heading, Some(243.98438));
+
+    assert!(matches!(&messages[5].alt_baro, Some(Altitude::Ground(ground)) if ground == "ground"));
+    assert_eq!(messages[5].gs, Some(18.0));
+
+    assert_eq!(messages[6].squawk.as_deref(), Some("7500"));
+    assert_eq!(messages[6].emergency.as_deref(), Some("unlawful"));
+
+    assert_eq!(messages[7].nav_altitude_mcp, Some(16992));
+    assert_eq!(messages[7].nav_altitude_fms, None);
+    assert!((messages[7].nav_qnh.unwrap_or_default() - 1012.8).abs() < 0.01);
+    assert_eq!(messages[7].sil_type, SilType::PerHour);
+
+    assert_eq!(messages[8].version, Some(2));
+    assert_eq!(messages[8].nac_p, Some(10));
+    assert_eq!(messages[8].gva, Some(2));
+
+    assert_eq!(messages[9].hex, "~2a4f1c");
+    assert_eq!(messages[9].flight.as_deref(), Some("N123AB  "));
+    assert_eq!(messages[10].adsb_type, "mode_s");
+
+    // Downlink formats without an address, such as DF1, are not decoded.
+    assert!(vec![0x08u8, 0x00, 0x17, 0x18, 0x3b, 0x7d, 0x0d]
+        .to_modes()
+        .is_err());
+    assert!(vec![0x8du8, 0x48, 0x40].to_modes().is_err());
+    Ok(())
+}
+
+/// This test validates the CRC of the saved frames and the correction of damaged ones,
+/// including the limits on DF11 and downlink format bits, and address recovery from address/parity.
+#[test]
+fn test_modes_crc() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/modes_01.txt")?;
+    for line in contents.lines() {
+        let frame: BeastMessage = line.to_avr()?;
+        assert_eq!(modes_syndrome(&frame.data), 0, "{}", line);
+        assert_eq!(frame.to_modes()?.quality, FrameQuality::Clean);
+    }
+
+    let clean: Vec<u8> = "*8D4840D6202CC371C32CE0576098;".to_avr()?.data;
+    let mut one_bit: Vec<u8> = clean.clone();
+    one_bit[5] ^= 0x80;
+    let corrected: ModeSMessage = one_bit.to_modes()?;
+    assert_eq!(corrected.quality, FrameQuality::Corrected(vec![40]));
+    assert_eq!(corrected.quality.corrected_bits(), 1);
+    assert_eq!(
+        corrected.to_adsb_json(0.0).flight.as_deref(),
+        Some("KLM1023 ")
+    );
+    assert!(ModeSMessage::from_frame(&one_bit, ErrorCorrection::Disabled).is_err());
+
+    let mut two_bits: Vec<u8> = one_bit.clone();
+    two_bits[8] ^= 0x02;
+    assert!(two_bits.to_modes().is_err());
+    let corrected: ModeSMessage = ModeSMessage::from_frame(&two_bits, ErrorCorrection::TwoBit)?;
+    assert_eq!(corrected.quality, FrameQuality::Corrected(vec![40, 70]));
+    assert_eq!(corrected.address, 0x4840d6);
+
+    // Errors in the downlink format are never corrected.
+    let mut format_bit: Vec<u8> = clean.clone();
+    format_bit[0] ^= 0x20;
+    assert_eq!(find_bit_errors(112, modes_syndrome(&format_bit), 2), None);
+
+    // DF11 is corrected by at most one bit, and accepts any interrogator code.
+    let all_call: Vec<u8> = "*5D4840D6F8740F;".to_avr()?.data;
+    let mut interrogator: Vec<u8> = all_call.clone();
+    interrogator[6] ^= 0x05;
+    assert_eq!(interrogator.to_modes()?.quality, FrameQuality::Clean);
+    let mut damaged: Vec<u8> = all_call.clone();
+    damaged[2] ^= 0x10;
+    assert_eq!(
+        damaged.to_modes()?.quality,
+        FrameQuality::Corrected(vec![19])
+    );
+    damaged[3] ^= 0x01;
+    assert!(ModeSMessage::from_frame(&damaged, ErrorCorrection::TwoBit).is_err());
+
+    let surveillance: ModeSMessage = "*20000000C82689;".to_avr()?.to_modes()?;
+    assert_eq!(surveillance.downlink_format, 4);
+    assert_eq!(surveillance.address, 0x4840d6);
+    assert_eq!(surveillance.quality, FrameQuality::AddressParity);
+    let comm_b: ModeSMessage = "*A0000000202CC371C32CE07A8D5D;".to_avr()?.to_modes()?;
+    assert_eq!(comm_b.downlink_format, 20);
+    assert_eq!(comm_b.hex(), "4840d6");
+    Ok(())
+}
+
+/// This test decodes Gillham, 25 foot and metric altitude codes and identity codes,
+/// and validates the altitude, squawk and flight status of surveillance replies converted to `ADSBJsonMessage`.
+#[test]
+fn test_modes_surveillance_replies() -> Result<(), Box<dyn Error>> {
+    for (ac13, altitude) in [
+        (0x0100, -1200),
+        (0x0400, -1000),
+        (0x040a, 0),
+        (0x1020, 2300),
+        (0x0328, 12700),
+        (0x0c83, 38000),
+        (0x0105, 62800),
+        (0x1838, 38000),
+    ] {
+        assert_eq!(decode_ac13(ac13), Some(altitude), "{:#06x}", ac13);
+    }
+    assert_eq!(decode_ac13(0), None);
+    // Gillham codes need one of the C bits, and only 5 of their 8 combinations are valid.
+    assert_eq!(decode_ac13(0x0002), None);
+    assert_eq!(decode_ac13(0x1500), None);
+    // 1000 metres with the M bit set.
+    assert_eq!(decode_ac13(0x07e8), Some(3281));
+    assert_eq!(decode_ac12(0x0c38), Some(38000));
+    assert_eq!(decode_id13(0x0aaa), "7700");
+    assert_eq!(decode_id13(0x1c09), "1234");
+
+    let gillham: ModeSMessage = "*20000328DB81D5;".to_avr()?.to_modes()?;
+    assert_eq!(gillham.downlink_format, 4);
+    assert_eq!(gillham.flight_status, Some(0));
+    assert_eq!(gillham.altitude, Some(12700));
+    assert_eq!(gillham.on_ground(), Some(false));
+    let gillham_json: ADSBJsonMessage = gillham.to_adsb_json(0.0);
+    assert!(matches!(gillham_json.alt_baro, Some(Altitude::I32(12700))));
+    assert_eq!(gillham_json.hex, "4840d6");
+    assert_eq!(gillham_json.adsb_type, "mode_s");
+
+    let alert: ADSBJsonMessage = "*220018380E392B;".to_avr()?.to_modes()?.to_adsb_json(0.0);
+    assert!(matches!(alert.alt_baro, Some(Altitude::I32(38000))));
+    assert_eq!((alert.alert, alert.spi), (Some(1), Some(0)));
+
+    let ground: ADSBJsonMessage = "*2100010AEDBA2D;".to_avr()?.to_modes()?.to_adsb_json(0.0);
+    assert!(matches!(&ground.alt_baro, Some(Altitude::Ground(ground)) if ground == "ground"));
+
+    let air_air: ModeSMessage = "*0400040ADFEFED;".to_avr()?.to_modes()?;
+    assert_eq!(air_air.vertical_status, Some(1));
+    assert_eq!(air_air.altitude, Some(0));
+    assert_eq!(air_air.on_ground(), Some(true));
+
+    let identity: ModeSMessage = "*2D001C0952C477;".to_avr()?.to_modes()?;
+    assert_eq!(identity.address, 0xa1b2c3);
+    assert_eq!(identity.squawk.as_deref(), Some("1234"));
+    assert_eq!(identity.altitude, None);
+    let identity_json: ADSBJsonMessage = identity.to_adsb_json(0.0);
+    assert_eq!(identity_json.squawk.as_deref(), Some("1234"));
+    assert_eq!(identity_json.spi, Some(1));
+
+    let comm_b_identity: ADSBJsonMessage = "*A8000AAA0000000000000061841C;"
+        .to_avr()?
+        .to_modes()?
+        .to_adsb_json(0.0);
+    assert_eq!(comm_b_identity.hex, "a1b2c3");
+    assert_eq!(comm_b_identity.squawk.as_deref(), Some("7700"));
+    Ok(())
+}
+
+/// This test infers and decodes the BDS register of saved Comm-B replies,
+/// validating each register's fields and the fields they fill in `ADSBJsonMessage`.
+#[test]
+fn test_modes_comm_b() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/comm_b_01.txt")?;
+    let messages: Vec<ModeSMessage> = contents
+        .lines()
+        .map(|line| line.to_avr()?.to_modes())
+        .collect::<Result<Vec<ModeSMessage>, _>>()?;
+    let registers: Vec<Option<&str>> = messages
+        .iter()
+        .map(|message| message.comm_b.as_ref().and_then(CommB::bds))
+        .collect();
+    assert_eq!(
+        registers,
+        vec![
+            Some("1,0"),
+            Some("1,7"),
+            Some("2,0"),
+            Some("3,0"),
+            Some("4,0"),
+            Some("4,4"),
+            Some("5,0"),
+            Some("6,0")
+        ]
+    );
+
+    match &messages[0].comm_b {
+        Some(CommB::DataLinkCapability(capability)) => {
+            assert!(capability.acas);
+            assert!(capability.specific_services);
+            assert!(!capability.enhanced_protocol);
+        }
+        other => panic!("Expected BDS 1,0, got {:?}", other),
+    }
+    match &messages[1].comm_b {
+        Some(CommB::GicbCapability(registers)) => assert_eq!(
+            registers,
+            &["0,5", "0,6", "0,7", "0,8", "0,9", "2,0", "4,0", "5,0", "5,1", "5,2", "6,0"]
+        ),
+        other => panic!("Expected BDS 1,7, got {:?}", other),
+    }
+    assert_eq!(
+        messages[2].comm_b,
+        Some(CommB::Identification("KLM1017 ".to_string()))
+    );
+    match &messages[3].comm_b {
+        Some(CommB::AcasResolutionAdvisory(advisory)) => {
+            assert_eq!(advisory.active_advisories, 0x2000);
+            assert!(!advisory.terminated);
+            assert_eq!(advisory.threat_address, Some(0xabcdef));
+        }
+        other => panic!("Expected BDS 3,0, got {:?}", other),
+    }
+    match &messages[5].comm_b {
+        Some(CommB::Meteorological(meteorological)) => {
+            assert_eq!(meteorological.wind_speed, 22);
+            assert!((meteorological.wind_direction - 344.5).abs() < 0.1);
+            assert_eq!(meteorological.temperature, -48.75);
+            assert_eq!(meteorological.pressure, None);
+        }
+        other => panic!("Expected BDS 4,4, got {:?}", other),
+    }
+
+    let now: f64 = 1675869370.0;
+    let identification: ADSBJsonMessage = messages[2].to_adsb_json(now);
+    assert_eq!(identification.hex, "484163");
+    assert_eq!(identification.flight.as_deref(), Some("KLM1017 "));
+
+    let intention: ADSBJsonMessage = messages[4].to_adsb_json(now);
+    assert_eq!(intention.nav_altitude_mcp, Some(3008));
+    assert_eq!(intention.nav_altitude_fms, Some(3008));
+    assert!((intention.nav_qnh.unwrap_or_default() - 1020.0).abs() < 0.01);
+    assert_eq!(intention.nav_modes, None);
+
+    let meteorological: ADSBJsonMessage = messages[5].to_adsb_json(now);
+    assert_eq!(
+        (meteorological.ws, meteorological.wd, meteorological.oat),
+        (Some(22), Some(345), Some(-49))
+    );
+
+    let track_and_turn: ADSBJsonMessage = messages[6].to_adsb_json(now);
+    assert!((track_and_turn.roll.unwrap_or_default() - 2.1).abs() < 0.01);
+    assert!((track_and_turn.track.unwrap_or_default() - 114.258).abs() < 0.001);
+    assert_eq!(track_and_turn.gs, Some(438.0));
+    assert_eq!(track_and_turn.track_rate, Some(0.125));
+    assert_eq!(track_and_turn.tas, Some(424));
+
+    let heading_and_speed: ADSBJsonMessage = messages[7].to_adsb_json(now);
+    assert!((heading_and_speed.mag_heading.unwrap_or_default() - 42.715).abs() < 0.001);
+    assert_eq!(heading_and_speed.ias, Some(252));
+    assert!((heading_and_speed.mach.unwrap_or_default() - 0.42).abs() < 0.001);
+    assert_eq!(heading_and_speed.baro_rate, Some(-1920));
+    assert_eq!(heading_and_speed.geom_rate, Some(-1920));
+
+    // A field that fits no register is kept as it is.
+    assert_eq!(
+        CommB::decode(&[0xff; 7]),
+        Some(CommB::Unknown(vec![0xff; 7]))
+    );
+    assert_eq!(CommB::decode(&[0; 7]), None);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::opensky::{icao_country, StateVector, POSITION_SOURCE_ADSB};
+use std::error::Error;
+use std::fs;
+
+/// This test converts an `aircraft.json` snapshot to OpenSky state vectors,
+/// validating the unit conversions, timestamps and origin country of each aircraft.
+#[test]
+fn test_opensky_state_vectors() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let state_vectors: Vec<StateVector> = snapshot.aircraft.iter().map(StateVector::from).collect();
+    assert_eq!(state_vectors.len(), snapshot.aircraft.len());
+
+    let airliner: &StateVector = &state_vectors[0];
+    assert_eq!(airliner.icao24, "a05dbc");
+    assert_eq!(airliner.callsign.as_deref(), Some("UAL488  "));
+    assert_eq!(airliner.origin_country, "United States");
+    assert!((airliner.last_contact - 1675869369.7).abs() < 1e-6);
+    assert_eq!(airliner.time_position, Some(1675869370.1));
+    assert!((airliner.latitude.ok_or("Missing latitude")? - 37.4405).abs() < 1e-4);
+    assert!((airliner.longitude.ok_or("Missing longitude")? + 106.5024).abs() < 1e-4);
+    assert!((airliner.baro_altitude.ok_or("Missing baro_altitude")? - 10363.2).abs() < 0.01);
+    assert!((airliner.geo_altitude.ok_or("Missing geo_altitude")? - 10401.3).abs() < 0.01);
+    assert!((airliner.velocity.ok_or("Missing velocity")? - 230.628).abs() < 0.01);
+    assert_eq!(airliner.true_track, Some(224.82));
+    assert_eq!(airliner.vertical_rate, Some(0.0));
+    assert!(!airliner.on_ground);
+    assert!(!airliner.spi);
+    assert_eq!(airliner.squawk.as_deref(), Some("0636"));
+    assert_eq!(airliner.position_source, POSITION_SOURCE_ADSB);
+    assert_eq!(airliner.category, Some(4));
+    assert_eq!(airliner.sensors, None);
+
+    let climbing: &StateVector = &state_vectors[2];
+    assert!((climbing.vertical_rate.ok_or("Missing vertical_rate")? - 0.32512).abs() < 1e-4);
+    Ok(())
+}
+
+/// This test validates the ICAO address allocation lookup, including unallocated addresses.
+#[test]
+fn test_opensky_icao_country() {
+    assert_eq!(icao_country(0xa05dbc), Some("United States"));
+    assert_eq!(icao_country(0x3c6444), Some("Germany"));
+    assert_eq!(icao_country(0x4ca2d1), Some("Ireland"));
+    assert_eq!(icao_country(0x7c6b2d), Some("Australia"));
+    assert_eq!(icao_country(0xc0175c), Some("Canada"));
+    assert_eq!(icao_country(0xfff000), None);
+}
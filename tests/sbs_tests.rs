@@ -0,0 +1,115 @@
+mod common;
+
+use adsb_parser::adsb_json::{ADSBJsonMessage, NewADSBJsonMessage};
+use adsb_parser::sbs::{NewSbsMessage, SbsMessage, SbsTransmissionType};
+use adsb_parser::{ADSBMessage, DecodeMessage};
+use chrono::{NaiveDate, NaiveDateTime};
+use std::error::Error;
+
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test ingests the SBS sample files as a message per line.
+/// It validates that every line decodes through both `to_sbs()` and `decode_message()` to the same message.
+#[test]
+fn test_sbs_parsing() -> Result<(), Box<dyn Error>> {
+    let sbs_messages: Vec<String> = combine_files_of_message_type(MessageType::Sbs)?;
+    assert!(!sbs_messages.is_empty());
+    for line in sbs_messages {
+        let message: SbsMessage = line.to_sbs()?;
+        match line.decode_message()? {
+            ADSBMessage::Sbs(decoded) => assert_eq!(decoded, message),
+            other => panic!("Expected an SBS message for {}, got {:?}", line, other),
+        }
+    }
+    Ok(())
+}
+
+/// This test validates the decoded columns of an airborne position line, and that malformed lines are rejected.
+#[test]
+fn test_sbs_message_contents() -> Result<(), Box<dyn Error>> {
+    let message: SbsMessage = "MSG,3,1,1,40621D,1,2023/02/05,15:14:24.220,2023/02/05,15:14:24.221,,38000,,,52.25720,3.91937,,,0,0,0,0".to_sbs()?;
+    let generated: Option<NaiveDateTime> = NaiveDate::from_ymd_opt(2023, 2, 5)
+        .and_then(|date| date.and_hms_milli_opt(15, 14, 24, 220));
+    assert_eq!(
+        message.transmission_type,
+        SbsTransmissionType::AirbornePosition
+    );
+    assert_eq!(message.hex_ident, "40621D");
+    assert_eq!(message.generated, generated);
+    assert_eq!(message.altitude, Some(38000));
+    assert_eq!(message.lat, Some(52.2572));
+    assert_eq!(message.lon, Some(3.91937));
+    assert_eq!(message.callsign, None);
+    assert_eq!(message.is_on_ground, Some(false));
+    let surface: SbsMessage =
+        "MSG,2,1,1,4841A7,1,2023/02/05,15:14:27.000,2023/02/05,15:14:27.000,,,17,92.8,52.32061,4.73473,,,,,,-1"
+            .to_sbs()?;
+    assert_eq!(surface.is_on_ground, Some(true));
+    assert!(
+        "MSG,9,1,1,40621D,1,2023/02/05,15:14:24.220,2023/02/05,15:14:24.221"
+            .to_sbs()
+            .is_err()
+    );
+    assert!(
+        "MSG,3,1,1,40621D,1,2023/02/30,15:14:24.220,2023/02/05,15:14:24.221"
+            .to_sbs()
+            .is_err()
+    );
+    assert!(
+        "MSG,3,1,1,40621D,1,2023/02/05,15:14:24.220,2023/02/05,15:14:24.221,,high"
+            .to_sbs()
+            .is_err()
+    );
+    assert!(
+        "STA,,5,179,400AE7,10103,2008/11/28,14:58:51.153,2008/11/28,14:58:51.153,RM"
+            .decode_message()
+            .is_err()
+    );
+    Ok(())
+}
+
+/// This test converts every ADSB JSON sample into BaseStation lines with `from_adsb_json()`.
+/// It validates that every produced line parses back with `to_sbs()` to the message it was written from.
+#[test]
+fn test_sbs_from_adsb_json() -> Result<(), Box<dyn Error>> {
+    let adsb_messages: Vec<String> = combine_files_of_message_type(MessageType::ADSBJson)?;
+    for line in adsb_messages {
+        if let Ok(adsb_message) = line.to_adsb() {
+            for sbs_message in SbsMessage::from_adsb_json(&adsb_message) {
+                let sbs_line: String = sbs_message.to_sbs_string();
+                assert_eq!(sbs_line.split(',').count(), 22, "{}", sbs_line);
+                let parsed: SbsMessage = sbs_line.to_sbs()?;
+                assert_eq!(parsed.transmission_type, sbs_message.transmission_type);
+                assert_eq!(parsed.hex_ident, sbs_message.hex_ident);
+                assert_eq!(parsed.callsign, sbs_message.callsign);
+                assert_eq!(parsed.squawk, sbs_message.squawk);
+                assert_eq!(parsed.altitude, sbs_message.altitude);
+            }
+        }
+    }
+    let adsb_message: ADSBJsonMessage = r#"{"now" : 1675610064.166,"hex":"a9ef5c","type":"adsb_icao","flight":"N73938  ","r":"N73938","t":"C172","alt_baro":10100,"alt_geom":10450,"gs":92.4,"track":326.48,"geom_rate":0,"squawk":"2664","emergency":"none","category":"A1","lat":35.682104,"lon":-107.188870,"nic":10,"rc":25,"seen_pos":0.000,"r_dst":42.400,"r_dir":314.6,"version":2,"nic_baro":0,"nac_p":10,"nac_v":1,"sil":3,"sil_type":"perhour","gva":2,"sda":2,"alert":0,"spi":0,"mlat":[],"tisb":[],"messages":16451,"seen":0.0,"rssi":-23.8}"#.to_adsb()?;
+    let sbs_lines: Vec<String> = SbsMessage::from_adsb_json(&adsb_message)
+        .iter()
+        .map(|message| message.to_sbs_string())
+        .collect();
+    assert_eq!(
+        sbs_lines,
+        vec![
+            "MSG,1,1,1,A9EF5C,1,2023/02/05,15:14:24.166,2023/02/05,15:14:24.166,N73938,,,,,,,,,,,0",
+            "MSG,3,1,1,A9EF5C,1,2023/02/05,15:14:24.166,2023/02/05,15:14:24.166,,10100,,,35.68210,-107.18887,,,0,0,0,0",
+            "MSG,4,1,1,A9EF5C,1,2023/02/05,15:14:24.166,2023/02/05,15:14:24.166,,,92.4,326.5,,,0,,,,,0",
+            "MSG,6,1,1,A9EF5C,1,2023/02/05,15:14:24.166,2023/02/05,15:14:24.166,,10100,,,,,,2664,0,0,0,0",
+        ]
+    );
+
+    let ground_message: ADSBJsonMessage = r#"{"now" : 1675610064.166,"hex":"a9ef5c","type":"adsb_icao","r":"N73938","alt_baro":"ground","gs":12.5,"track":45.0,"lat":35.682104,"lon":-107.188870,"nic":10,"rc":25,"seen_pos":0.000,"version":2,"sil_type":"perhour","mlat":[],"tisb":[],"messages":16451,"seen":0.0,"rssi":-23.8}"#.to_adsb()?;
+    let sbs_lines: Vec<String> = SbsMessage::from_adsb_json(&ground_message)
+        .iter()
+        .map(|message| message.to_sbs_string())
+        .collect();
+    assert_eq!(
+        sbs_lines[0],
+        "MSG,2,1,1,A9EF5C,1,2023/02/05,15:14:24.166,2023/02/05,15:14:24.166,,,12.5,45.0,35.68210,-107.18887,,,,,,-1"
+    );
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use adsb_parser::adsb_json::Altitude;
+use adsb_parser::trace::{NewTraceFile, TraceFile};
+use std::error::Error;
+use std::fs;
+
+/// This test reads a globe history trace file and validates the decoded aircraft and points,
+/// including the optional details object and the columns older readsb versions leave out.
+#[test]
+fn test_trace_parsing() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/trace_full_a05dbc.json")?;
+    let trace_file: TraceFile = contents.to_trace()?;
+    assert_eq!(trace_file.icao, "a05dbc");
+    assert_eq!(trace_file.registration.as_deref(), Some("N12218"));
+    assert_eq!(trace_file.description.as_deref(), Some("BOEING 737-800"));
+    assert_eq!(trace_file.trace.len(), 6);
+
+    let takeoff = &trace_file.trace[0];
+    assert_eq!(takeoff.timestamp, 1675864800.0);
+    assert!(matches!(takeoff.altitude, Some(Altitude::Ground(_))));
+    assert!(takeoff.is_new_leg());
+    assert_eq!(takeoff.vertical_rate, None);
+    let details = takeoff.details.as_ref().ok_or("Missing trace details")?;
+    assert_eq!(details.flight.as_deref(), Some("UAL488  "));
+    assert_eq!(details.nav_altitude_mcp, Some(34016));
+
+    let climb = &trace_file.trace[2];
+    assert_eq!(climb.timestamp, 1675864800.0 + 498.1);
+    assert!(matches!(climb.altitude, Some(Altitude::I32(8125))));
+    assert!(climb.is_geometric_rate());
+    assert_eq!(climb.ias, Some(201));
+    assert_eq!(climb.roll, Some(12.3));
+    assert!(trace_file.trace[3].is_stale());
+
+    let short = &trace_file.trace[4];
+    assert_eq!(short.source, None);
+    assert_eq!(short.alt_geom, None);
+    assert!(trace_file.trace[5].is_geometric_altitude());
+    assert_eq!(trace_file.trace[5].source.as_deref(), Some("mlat"));
+
+    assert!(
+        r#"{"icao":"a05dbc","timestamp":1675864800.0,"trace":[[0.0,39.8,-104.6]]}"#
+            .to_trace()
+            .is_err()
+    );
+    assert!(
+        r#"{"icao":"a05dbc","timestamp":1675864800.0,"trace":[[0.0,null,-104.6,null,null,null,0,null]]}"#
+            .to_trace()
+            .is_err()
+    );
+    Ok(())
+}
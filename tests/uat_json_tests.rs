@@ -0,0 +1,52 @@
+mod common;
+
+use adsb_parser::uat_json::{NewUatJsonMessage, UatJsonMessage};
+use adsb_parser::{ADSBMessage, DecodeMessage};
+use std::error::Error;
+
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test ingests the dump978-fa sample files as a message per line.
+/// It validates that every line decodes through both `to_uat_json()` and `decode_message()` to the same message,
+/// and that serialising the message gives back the same JSON.
+#[test]
+fn test_uat_json_parsing() -> Result<(), Box<dyn Error>> {
+    let uat_messages: Vec<String> = combine_files_of_message_type(MessageType::Uat)?;
+    assert!(!uat_messages.is_empty());
+    for line in uat_messages {
+        let message: UatJsonMessage = line.to_uat_json()?;
+        match line.decode_message()? {
+            ADSBMessage::Uat(decoded) => assert_eq!(decoded, message),
+            other => panic!("Expected a UAT message for {}, got {:?}", line, other),
+        }
+        let original: serde_json::Value = serde_json::from_str(&line)?;
+        let serialised: serde_json::Value = serde_json::from_str(&message.to_string()?)?;
+        assert_eq!(original, serialised);
+    }
+    Ok(())
+}
+
+/// This test validates the decoded fields of a mode status message.
+#[test]
+fn test_uat_json_message_contents() -> Result<(), Box<dyn Error>> {
+    let uat_messages: Vec<String> = combine_files_of_message_type(MessageType::Uat)?;
+    let message: UatJsonMessage = uat_messages[1].to_uat_json()?;
+    assert_eq!(message.address, "a7c2b4");
+    assert_eq!(message.address_qualifier, "adsb_icao");
+    assert_eq!(message.callsign.as_deref(), Some("N588TR"));
+    assert_eq!(message.flightplan_id.as_deref(), Some("1200"));
+    assert_eq!(message.pressure_altitude, Some(4400));
+    assert_eq!(message.uat_version, Some(2));
+    let metadata = message.metadata.ok_or("Missing metadata")?;
+    assert_eq!(metadata.received_at, Some(1675610065.118));
+    assert_eq!(metadata.errors, Some(2));
+    let position = message.position.ok_or("Missing position")?;
+    assert_eq!(position.lat, 35.08771);
+    assert!(
+        message
+            .capability_codes
+            .ok_or("Missing capability codes")?
+            .uat_in
+    );
+    Ok(())
+}
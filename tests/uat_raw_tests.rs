@@ -0,0 +1,105 @@
+mod common;
+
+use adsb_parser::uat_json::UatJsonMessage;
+use adsb_parser::uat_raw::NewUatRawMessage;
+use adsb_parser::{ADSBMessage, DecodeMessage};
+use std::error::Error;
+
+use crate::common::{combine_files_of_message_type, MessageType};
+
+/// This test ingests the raw UAT sample files as a message per line.
+/// It validates that every line decodes through both `to_uat_raw()` and `decode_message()` to the same message.
+#[test]
+fn test_uat_raw_parsing() -> Result<(), Box<dyn Error>> {
+    let raw_messages: Vec<String> = combine_files_of_message_type(MessageType::UatRaw)?;
+    assert!(!raw_messages.is_empty());
+    for line in raw_messages {
+        let message: UatJsonMessage = line.to_uat_raw()?;
+        match line.decode_message()? {
+            ADSBMessage::Uat(decoded) => assert_eq!(decoded, message),
+            other => panic!("Expected a UAT message for {}, got {:?}", line, other),
+        }
+    }
+    Ok(())
+}
+
+/// This test validates the decoded elements of long airborne, basic TIS-B and long surface payloads.
+#[test]
+fn test_uat_raw_message_contents() -> Result<(), Box<dyn Error>> {
+    let raw_messages: Vec<String> = combine_files_of_message_type(MessageType::UatRaw)?;
+
+    let airborne: UatJsonMessage = raw_messages[0].to_uat_raw()?;
+    assert_eq!(airborne.address, "a7c2b4");
+    assert_eq!(airborne.address_qualifier, "adsb_icao");
+    let metadata = airborne.metadata.ok_or("Missing metadata")?;
+    assert_eq!(metadata.errors, Some(2));
+    assert_eq!(metadata.rssi, Some(-12.0));
+    assert_eq!(metadata.received_at, Some(1675610065.118));
+    let position = airborne.position.ok_or("Missing position")?;
+    assert!((position.lat - 35.08771).abs() < 1e-4);
+    assert!((position.lon + 106.60903).abs() < 1e-4);
+    assert_eq!(airborne.nic, Some(8));
+    assert_eq!(airborne.horizontal_containment, Some(185.2));
+    assert_eq!(airborne.airground_state.as_deref(), Some("airborne"));
+    assert_eq!(airborne.pressure_altitude, Some(4400));
+    assert_eq!(airborne.geometric_altitude, Some(4575));
+    assert_eq!(airborne.north_velocity, Some(71));
+    assert_eq!(airborne.east_velocity, Some(-64));
+    assert_eq!(airborne.ground_speed, Some(95.6));
+    assert_eq!(airborne.true_track, Some(317.97));
+    assert_eq!(airborne.vertical_velocity_barometric, Some(-128));
+    assert_eq!(airborne.utc_coupled, Some(true));
+    assert_eq!(airborne.callsign.as_deref(), Some("N588TR"));
+    assert_eq!(airborne.emitter_category.as_deref(), Some("A1"));
+    assert_eq!(airborne.emergency.as_deref(), Some("none"));
+    assert_eq!(airborne.uat_version, Some(2));
+    assert_eq!(airborne.sil, Some(3));
+    assert_eq!(airborne.transmit_mso, Some(38));
+    assert_eq!(airborne.nac_p, Some(10));
+    assert_eq!(airborne.nic_baro, Some(1));
+    assert_eq!(airborne.gva, Some(2));
+    assert_eq!(airborne.sil_supplement.as_deref(), Some("per_hour"));
+    assert!(
+        airborne
+            .capability_codes
+            .ok_or("Missing capability codes")?
+            .uat_in
+    );
+
+    let tisb: UatJsonMessage = raw_messages[1].to_uat_raw()?;
+    assert_eq!(tisb.address_qualifier, "tisb_trackfile");
+    assert_eq!(tisb.tisb_site_id, Some(3));
+    assert_eq!(tisb.utc_coupled, None);
+    assert_eq!(tisb.pressure_altitude, Some(6300));
+    assert_eq!(tisb.vertical_velocity_barometric, None);
+    assert_eq!(tisb.callsign, None);
+
+    let surface: UatJsonMessage = raw_messages[2].to_uat_raw()?;
+    assert_eq!(surface.airground_state.as_deref(), Some("ground"));
+    assert_eq!(surface.geometric_altitude, Some(5325));
+    assert_eq!(surface.pressure_altitude, Some(5150));
+    assert_eq!(surface.ground_speed, Some(8.0));
+    assert_eq!(surface.true_heading, Some(82.97));
+    assert_eq!(surface.true_track, None);
+    let size = surface.aircraft_size.ok_or("Missing aircraft size")?;
+    assert_eq!((size.length, size.width), (15.0, 23.0));
+
+    let southern: UatJsonMessage = raw_messages[3].to_uat_raw()?;
+    let position = southern.position.ok_or("Missing position")?;
+    assert!((position.lat + 33.9461).abs() < 1e-4);
+    assert!((position.lon - 151.1772).abs() < 1e-4);
+    assert_eq!(southern.flightplan_id.as_deref(), Some("1200"));
+    assert_eq!(southern.callsign, None);
+    assert_eq!(southern.emergency.as_deref(), Some("minfuel"));
+    assert_eq!(southern.vertical_velocity_geometric, Some(64));
+    assert_eq!(southern.sil_supplement.as_deref(), Some("per_sample"));
+    let operational_modes = southern
+        .operational_modes
+        .ok_or("Missing operational modes")?;
+    assert!(operational_modes.ident_active);
+    assert!(!operational_modes.tcas_ra_active);
+
+    assert!("-08a7c2b4;rs=0;".to_uat_raw().is_err());
+    assert!("+08a7c2b4;".to_uat_raw().is_err());
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use adsb_parser::adsb_json::{ADSBJsonMessage, Altitude};
+use adsb_parser::aircraft_json::{AircraftSnapshot, NewAircraftSnapshot};
+use adsb_parser::vrs::{NewVrsAircraftList, VrsAircraft, VrsAircraftList};
+use std::error::Error;
+use std::fs;
+
+/// This test reads a saved VRS `AircraftList.json` and converts it to `ADSBJsonMessage`,
+/// validating the unit conversions and that aircraft without a position are kept without one.
+#[test]
+fn test_vrs_import() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/vrs_aircraftlist_01.json")?;
+    let aircraft_list: VrsAircraftList = contents.to_vrs()?;
+    assert_eq!(aircraft_list.ac_list.len(), 3);
+    assert_eq!(aircraft_list.total_ac, Some(3));
+    assert_eq!(aircraft_list.stm, Some(1675869370123));
+    assert_eq!(
+        aircraft_list.ac_list[0].aircraft_type.as_deref(),
+        Some("B738")
+    );
+
+    let snapshot: AircraftSnapshot = aircraft_list.to_snapshot();
+    assert_eq!(snapshot.now, 1675869370.123);
+    assert_eq!(snapshot.aircraft.len(), 3);
+    assert_eq!(snapshot.messages, 302);
+
+    let airliner: &ADSBJsonMessage = &snapshot.aircraft[0];
+    assert_eq!(airliner.hex, "a05dbc");
+    assert_eq!(airliner.adsb_type, "adsb_icao");
+    assert_eq!(airliner.aircraft_registration, "N12218");
+    assert_eq!(airliner.flight.as_deref(), Some("UAL488"));
+    assert!(matches!(airliner.alt_baro, Some(Altitude::I32(34000))));
+    assert_eq!(airliner.nav_qnh, Some(1013.5));
+    assert_eq!(airliner.baro_rate, Some(0));
+    assert_eq!(airliner.track, Some(224.82));
+    assert!((airliner.r_dst.unwrap_or_default() - 135.277).abs() < 0.01);
+    assert!((airliner.seen_pos.ok_or("Missing seen_pos")? - 0.023).abs() < 1e-6);
+    assert_eq!(airliner.description.as_deref(), Some("Boeing 737-824"));
+    assert_eq!(airliner.dbflags, Some(0));
+
+    let military: &ADSBJsonMessage = &snapshot.aircraft[1];
+    assert_eq!(military.adsb_type, "mlat");
+    assert_eq!(military.geom_rate, Some(-640));
+    assert_eq!(military.baro_rate, None);
+    assert_eq!(military.emergency.as_deref(), Some("general"));
+    assert_eq!(military.dbflags, Some(1));
+
+    let no_position: &ADSBJsonMessage = &snapshot.aircraft[2];
+    assert_eq!(no_position.hex, "a0d8e0");
+    assert_eq!(no_position.position(), None);
+    assert_eq!(no_position.r_dst, None);
+    Ok(())
+}
+
+/// This test converts an `aircraft.json` snapshot to a VRS aircraft list and back,
+/// validating the VRS short keys and that the round trip keeps the state of each aircraft.
+#[test]
+fn test_vrs_export() -> Result<(), Box<dyn Error>> {
+    let contents: String = fs::read_to_string("test_files/aircraft_01.json")?;
+    let snapshot: AircraftSnapshot = contents.to_aircraft_snapshot()?;
+    let aircraft_list: VrsAircraftList = VrsAircraftList::from_snapshot(&snapshot);
+    assert_eq!(aircraft_list.ac_list.len(), snapshot.aircraft.len());
+
+    let exported: serde_json::Value = serde_json::from_str(&aircraft_list.to_string()?)?;
+    let first = &exported["acList"][0];
+    assert_eq!(first["Icao"], "A05DBC");
+    assert_eq!(first["Id"], 0xa05dbc);
+    assert_eq!(first["Call"], "UAL488");
+    assert_eq!(
+        first["Long"].as_f64().map(|lon| lon as f32),
+        snapshot.aircraft[0].lon
+    );
+    assert_eq!(first["Sqk"], "0636");
+    assert_eq!(first["Type"], "B738");
+
+    let vrs_aircraft: VrsAircraft = VrsAircraft::from_adsb_json(&snapshot.aircraft[0]);
+    let message: ADSBJsonMessage = vrs_aircraft.to_adsb_json(snapshot.now);
+    assert_eq!(message.hex, snapshot.aircraft[0].hex);
+    assert_eq!(message.lat, snapshot.aircraft[0].lat);
+    assert_eq!(message.alt_geom, snapshot.aircraft[0].alt_geom);
+    assert_eq!(
+        message.nav_altitude_mcp,
+        snapshot.aircraft[0].nav_altitude_mcp
+    );
+    assert_eq!(message.seen_pos, snapshot.aircraft[0].seen_pos);
+    assert!(
+        (message.r_dst.unwrap_or_default() - snapshot.aircraft[0].r_dst.unwrap_or_default()).abs()
+            < 0.001
+    );
+
+    let no_position: VrsAircraft = VrsAircraft {
+        lat: None,
+        ..vrs_aircraft
+    };
+    assert_eq!(no_position.to_adsb_json(snapshot.now).position(), None);
+    Ok(())
+}